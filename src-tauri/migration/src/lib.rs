@@ -1,12 +1,52 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
+mod m20251018_000001_create_review_log;
+mod m20251018_000002_create_fsrs_params;
+mod m20251018_000003_create_app_setting;
+mod m20251018_000004_add_learning_step;
+mod m20251018_000005_add_review_log_snapshot;
+mod m20251018_000006_create_word_sense_and_phrase;
+mod m20251018_000007_normalize_source;
+mod m20251018_000008_create_word_book;
+mod m20251018_000009_add_frequency_rank;
+mod m20251018_000010_add_ipa_and_inflection;
+mod m20251018_000011_create_master_word_fts;
+mod m20251018_000012_add_sense_pinyin;
+mod m20251018_000013_add_resume_status;
+mod m20251018_000014_add_book_word_count;
+mod m20251018_000015_create_definition_trigram_fts;
+mod m20251018_000016_add_master_word_text_nocase_index;
+mod m20251018_000017_add_word_list_indexes;
+mod m20251018_000018_unique_user_word_master_word;
+mod m20251018_000019_split_sense_pinyin_syllables;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_table::Migration),
+            Box::new(m20251018_000001_create_review_log::Migration),
+            Box::new(m20251018_000002_create_fsrs_params::Migration),
+            Box::new(m20251018_000003_create_app_setting::Migration),
+            Box::new(m20251018_000004_add_learning_step::Migration),
+            Box::new(m20251018_000005_add_review_log_snapshot::Migration),
+            Box::new(m20251018_000006_create_word_sense_and_phrase::Migration),
+            Box::new(m20251018_000007_normalize_source::Migration),
+            Box::new(m20251018_000008_create_word_book::Migration),
+            Box::new(m20251018_000009_add_frequency_rank::Migration),
+            Box::new(m20251018_000010_add_ipa_and_inflection::Migration),
+            Box::new(m20251018_000011_create_master_word_fts::Migration),
+            Box::new(m20251018_000012_add_sense_pinyin::Migration),
+            Box::new(m20251018_000013_add_resume_status::Migration),
+            Box::new(m20251018_000014_add_book_word_count::Migration),
+            Box::new(m20251018_000015_create_definition_trigram_fts::Migration),
+            Box::new(m20251018_000016_add_master_word_text_nocase_index::Migration),
+            Box::new(m20251018_000017_add_word_list_indexes::Migration),
+            Box::new(m20251018_000018_unique_user_word_master_word::Migration),
+            Box::new(m20251018_000019_split_sense_pinyin_syllables::Migration),
+        ]
     }
 }
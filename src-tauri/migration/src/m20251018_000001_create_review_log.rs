@@ -0,0 +1,84 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // ReviewLog 表：每次提交评分都追加一条，不做修改
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReviewLog::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ReviewLog::Id))
+                    .col(ColumnDef::new(ReviewLog::UserWordId).integer().not_null())
+                    .col(ColumnDef::new(ReviewLog::Rating).small_integer().not_null())
+                    .col(ColumnDef::new(ReviewLog::ElapsedDays).float().not_null())
+                    .col(ColumnDef::new(ReviewLog::ScheduledDays).float().not_null())
+                    .col(ColumnDef::new(ReviewLog::PrevStability).float().not_null())
+                    .col(ColumnDef::new(ReviewLog::PrevDifficulty).float().not_null())
+                    .col(ColumnDef::new(ReviewLog::NewStability).float().not_null())
+                    .col(ColumnDef::new(ReviewLog::NewDifficulty).float().not_null())
+                    .col(ColumnDef::new(ReviewLog::DurationMs).big_integer())
+                    .col(
+                        ColumnDef::new(ReviewLog::ReviewedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-review_log-user_word_id")
+                            .from(ReviewLog::Table, ReviewLog::UserWordId)
+                            .to(UserWord::Table, UserWord::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-review_log-user_word_id")
+                    .table(ReviewLog::Table)
+                    .col(ReviewLog::UserWordId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ReviewLog::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReviewLog {
+    Table,
+    Id,
+    UserWordId,
+    Rating,         // 评分 1~4
+    ElapsedDays,    // 距离上次复习的天数
+    ScheduledDays,  // 本次安排的间隔天数
+    PrevStability,  // 复习前稳定性
+    PrevDifficulty, // 复习前难度
+    NewStability,   // 复习后稳定性
+    NewDifficulty,  // 复习后难度
+    DurationMs,     // 作答用时 (毫秒)
+    ReviewedAt,     // 复习时间
+}
+
+#[derive(DeriveIden)]
+enum UserWord {
+    Table,
+    Id,
+}
@@ -0,0 +1,55 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // FsrsParams 表：优化器拟合出的个人参数，deck 为空代表全局参数
+        manager
+            .create_table(
+                Table::create()
+                    .table(FsrsParams::Table)
+                    .if_not_exists()
+                    .col(pk_auto(FsrsParams::Id))
+                    .col(ColumnDef::new(FsrsParams::Deck).string())
+                    .col(ColumnDef::new(FsrsParams::Params).string().not_null())
+                    .col(ColumnDef::new(FsrsParams::LogLoss).float())
+                    .col(
+                        ColumnDef::new(FsrsParams::ReviewCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(FsrsParams::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(FsrsParams::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum FsrsParams {
+    Table,
+    Id,
+    Deck,        // 词书 (对应 master_word.source)，为空代表全局
+    Params,      // 19 个参数的 JSON 数组
+    LogLoss,     // 拟合后的对数损失
+    ReviewCount, // 参与拟合的复习次数
+    UpdatedAt,
+}
@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // AppSetting 表：后端需要用到的设置，键值对存储，值为 JSON
+        manager
+            .create_table(
+                Table::create()
+                    .table(AppSetting::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AppSetting::Key)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AppSetting::Value).string().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AppSetting::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AppSetting {
+    Table,
+    Key,   // 设置项名称
+    Value, // JSON 编码的值
+}
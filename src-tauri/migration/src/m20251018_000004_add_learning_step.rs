@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 学习 / 重学时当前处在第几步
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserWord::Table)
+                    .add_column(
+                        ColumnDef::new(UserWord::LearningStep)
+                            .small_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 以前复习过的单词 status 一律是 1，现在 1 代表 Learning，改成 2 (Review)
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(UserWord::Table)
+                    .value(UserWord::Status, 2)
+                    .and_where(Expr::col(UserWord::Status).eq(1))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(UserWord::Table)
+                    .value(UserWord::Status, 1)
+                    .and_where(Expr::col(UserWord::Status).ne(0))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserWord::Table)
+                    .drop_column(UserWord::LearningStep)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserWord {
+    Table,
+    Status,
+    LearningStep,
+}
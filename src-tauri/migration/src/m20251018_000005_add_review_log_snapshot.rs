@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 复习前的完整调度状态，撤销时用来还原
+        // 以前的日志没有这些数据，所以都允许为空
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReviewLog::Table)
+                    .add_column(ColumnDef::new(ReviewLog::PrevDue).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReviewLog::Table)
+                    .add_column(
+                        ColumnDef::new(ReviewLog::PrevLastReview).timestamp_with_time_zone(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReviewLog::Table)
+                    .add_column(ColumnDef::new(ReviewLog::PrevStatus).small_integer())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReviewLog::Table)
+                    .add_column(ColumnDef::new(ReviewLog::PrevLearningStep).small_integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 一次只能删一列
+        for col in [
+            ReviewLog::PrevLearningStep,
+            ReviewLog::PrevStatus,
+            ReviewLog::PrevLastReview,
+            ReviewLog::PrevDue,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(ReviewLog::Table)
+                        .drop_column(col)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReviewLog {
+    Table,
+    PrevDue,          // 复习前的到期时间
+    PrevLastReview,   // 复习前的上次复习时间
+    PrevStatus,       // 复习前的状态
+    PrevLearningStep, // 复习前的学习步骤
+}
@@ -0,0 +1,144 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // WordSense 表：单词按词性拆开的释义
+        manager
+            .create_table(
+                Table::create()
+                    .table(WordSense::Table)
+                    .if_not_exists()
+                    .col(pk_auto(WordSense::Id))
+                    .col(ColumnDef::new(WordSense::MasterWordId).integer().not_null())
+                    .col(ColumnDef::new(WordSense::Pos).string())
+                    .col(ColumnDef::new(WordSense::Translation).string().not_null())
+                    .col(
+                        ColumnDef::new(WordSense::SortOrder)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-word_sense-master_word_id")
+                            .from(WordSense::Table, WordSense::MasterWordId)
+                            .to(MasterWord::Table, MasterWord::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // WordPhrase 表：单词的常用短语 / 例句
+        manager
+            .create_table(
+                Table::create()
+                    .table(WordPhrase::Table)
+                    .if_not_exists()
+                    .col(pk_auto(WordPhrase::Id))
+                    .col(
+                        ColumnDef::new(WordPhrase::MasterWordId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WordPhrase::Phrase).string().not_null())
+                    .col(ColumnDef::new(WordPhrase::Translation).string().not_null())
+                    .col(
+                        ColumnDef::new(WordPhrase::SortOrder)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-word_phrase-master_word_id")
+                            .from(WordPhrase::Table, WordPhrase::MasterWordId)
+                            .to(MasterWord::Table, MasterWord::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-word_sense-master_word_id")
+                    .table(WordSense::Table)
+                    .col(WordSense::MasterWordId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-word_phrase-master_word_id")
+                    .table(WordPhrase::Table)
+                    .col(WordPhrase::MasterWordId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // 以前释义用字面量 "/n" 拼接，改成真正的换行
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(MasterWord::Table)
+                    .value(
+                        MasterWord::Definition,
+                        Func::cust(Alias::new("REPLACE")).args([
+                            Expr::col(MasterWord::Definition).into(),
+                            Expr::val("/n").into(),
+                            Expr::val("\n").into(),
+                        ]),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WordPhrase::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(WordSense::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum WordSense {
+    Table,
+    Id,
+    MasterWordId,
+    Pos,         // 词性，比如 "n." "v."，可能没有
+    Translation, // 这个词性下的中文释义
+    SortOrder,   // 在原词典里的顺序
+}
+
+#[derive(DeriveIden)]
+enum WordPhrase {
+    Table,
+    Id,
+    MasterWordId,
+    Phrase,      // 短语原文
+    Translation, // 短语释义
+    SortOrder,
+}
+
+#[derive(DeriveIden)]
+enum MasterWord {
+    Table,
+    Id,
+    Definition,
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 以前内置词书的来源写的是中文 "高中"，统一成 Source 枚举的字符串形式
+const OLD_HIGH_SCHOOL: &str = "高中";
+const HIGH_SCHOOL: &str = "HighSchool";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rename_source(manager, OLD_HIGH_SCHOOL, HIGH_SCHOOL).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rename_source(manager, HIGH_SCHOOL, OLD_HIGH_SCHOOL).await
+    }
+}
+
+async fn rename_source(manager: &SchemaManager<'_>, from: &str, to: &str) -> Result<(), DbErr> {
+    manager
+        .exec_stmt(
+            Query::update()
+                .table(MasterWord::Table)
+                .value(MasterWord::Source, to)
+                .and_where(Expr::col(MasterWord::Source).eq(from))
+                .to_owned(),
+        )
+        .await?;
+
+    // 按词书保存的记忆参数也跟着改
+    manager
+        .exec_stmt(
+            Query::update()
+                .table(FsrsParams::Table)
+                .value(FsrsParams::Deck, to)
+                .and_where(Expr::col(FsrsParams::Deck).eq(from))
+                .to_owned(),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[derive(DeriveIden)]
+enum MasterWord {
+    Table,
+    Source,
+}
+
+#[derive(DeriveIden)]
+enum FsrsParams {
+    Table,
+    Deck,
+}
@@ -0,0 +1,238 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 每个 Source 对应一本词书，code 是 Source 的字符串形式
+const BOOKS: [(&str, &str); 7] = [
+    ("ElementarySchool", "小学"),
+    ("JuniorHighSchool", "初中"),
+    ("HighSchool", "高中"),
+    ("CET4", "大学英语四级"),
+    ("CET6", "大学英语六级"),
+    ("Custom", "自定义"),
+    ("Other", "其他"),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WordBook::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WordBook::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(WordBook::Code)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(WordBook::Name).string().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        let mut insert = Query::insert()
+            .into_table(WordBook::Table)
+            .columns([WordBook::Code, WordBook::Name])
+            .to_owned();
+        for (code, name) in BOOKS {
+            insert.values_panic([code.into(), name.into()]);
+        }
+        manager.exec_stmt(insert).await?;
+
+        // 单词和词书多对多：一个单词可以同时属于小学和四级
+        manager
+            .create_table(
+                Table::create()
+                    .table(WordBookMembership::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WordBookMembership::WordBookId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WordBookMembership::MasterWordId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WordBookMembership::SortOrder)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(WordBookMembership::WordBookId)
+                            .col(WordBookMembership::MasterWordId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-word_book_membership-word_book_id")
+                            .from(WordBookMembership::Table, WordBookMembership::WordBookId)
+                            .to(WordBook::Table, WordBook::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-word_book_membership-master_word_id")
+                            .from(WordBookMembership::Table, WordBookMembership::MasterWordId)
+                            .to(MasterWord::Table, MasterWord::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-word_book_membership-master_word_id")
+                    .table(WordBookMembership::Table)
+                    .col(WordBookMembership::MasterWordId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // 已有单词按 source 放进对应的词书，按入库顺序排序
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(WordBookMembership::Table)
+                    .columns([
+                        WordBookMembership::WordBookId,
+                        WordBookMembership::MasterWordId,
+                        WordBookMembership::SortOrder,
+                    ])
+                    .select_from(
+                        Query::select()
+                            .column((WordBook::Table, WordBook::Id))
+                            .column((MasterWord::Table, MasterWord::Id))
+                            .column((MasterWord::Table, MasterWord::Id))
+                            .from(MasterWord::Table)
+                            .inner_join(
+                                WordBook::Table,
+                                Expr::col((WordBook::Table, WordBook::Code))
+                                    .equals((MasterWord::Table, MasterWord::Source)),
+                            )
+                            .to_owned(),
+                    )
+                    .map_err(|e| DbErr::Migration(e.to_string()))?
+                    .to_owned(),
+            )
+            .await?;
+
+        // 不认识的来源归到 "其他"
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(WordBookMembership::Table)
+                    .columns([
+                        WordBookMembership::WordBookId,
+                        WordBookMembership::MasterWordId,
+                        WordBookMembership::SortOrder,
+                    ])
+                    .select_from(
+                        Query::select()
+                            .column((WordBook::Table, WordBook::Id))
+                            .column((MasterWord::Table, MasterWord::Id))
+                            .column((MasterWord::Table, MasterWord::Id))
+                            .from(MasterWord::Table)
+                            .from(WordBook::Table)
+                            .and_where(Expr::col((WordBook::Table, WordBook::Code)).eq("Other"))
+                            .and_where(
+                                Expr::col((MasterWord::Table, MasterWord::Source)).is_not_null(),
+                            )
+                            .and_where(
+                                Expr::col((MasterWord::Table, MasterWord::Source)).not_in_subquery(
+                                    Query::select()
+                                        .column(WordBook::Code)
+                                        .from(WordBook::Table)
+                                        .to_owned(),
+                                ),
+                            )
+                            .to_owned(),
+                    )
+                    .map_err(|e| DbErr::Migration(e.to_string()))?
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MasterWord::Table)
+                    .drop_column(MasterWord::Source)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MasterWord::Table)
+                    .add_column(ColumnDef::new(MasterWord::Source).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 属于多本词书的单词只能还原成其中一本
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE master_word SET source = (
+                    SELECT word_book.code FROM word_book_membership
+                    INNER JOIN word_book ON word_book.id = word_book_membership.word_book_id
+                    WHERE word_book_membership.master_word_id = master_word.id
+                    ORDER BY word_book.id LIMIT 1
+                )",
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(WordBookMembership::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(WordBook::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum WordBook {
+    Table,
+    Id,
+    Code, // Source 的字符串形式，比如 "CET4"
+    Name, // 显示用的名字
+}
+
+#[derive(DeriveIden)]
+enum WordBookMembership {
+    Table,
+    WordBookId,
+    MasterWordId,
+    SortOrder, // 单词在词书里的原始顺序
+}
+
+#[derive(DeriveIden)]
+enum MasterWord {
+    Table,
+    Id,
+    Source,
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 词频排名，1 是最常用的词；词频表里没有的词为空
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MasterWord::Table)
+                    .add_column(ColumnDef::new(MasterWord::FrequencyRank).integer())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-master_word-frequency_rank")
+                    .table(MasterWord::Table)
+                    .col(MasterWord::FrequencyRank)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-master_word-frequency_rank")
+                    .table(MasterWord::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MasterWord::Table)
+                    .drop_column(MasterWord::FrequencyRank)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum MasterWord {
+    Table,
+    FrequencyRank,
+}
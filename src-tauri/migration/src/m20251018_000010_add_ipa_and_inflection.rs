@@ -0,0 +1,119 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 美式 / 英式音标，由词典数据补上；pronunciation 仍是默认显示的音标
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MasterWord::Table)
+                    .add_column(ColumnDef::new(MasterWord::PronunciationUs).string())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MasterWord::Table)
+                    .add_column(ColumnDef::new(MasterWord::PronunciationUk).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        // WordInflection 表：单词的变形 (过去式、复数、-ing 等)
+        manager
+            .create_table(
+                Table::create()
+                    .table(WordInflection::Table)
+                    .if_not_exists()
+                    .col(pk_auto(WordInflection::Id))
+                    .col(
+                        ColumnDef::new(WordInflection::MasterWordId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WordInflection::Form).string().not_null())
+                    .col(ColumnDef::new(WordInflection::Kind).string().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-word_inflection-master_word_id")
+                            .from(WordInflection::Table, WordInflection::MasterWordId)
+                            .to(MasterWord::Table, MasterWord::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 同一个单词的同一种变形只存一次
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-word_inflection-master_word_id-kind-form")
+                    .table(WordInflection::Table)
+                    .col(WordInflection::MasterWordId)
+                    .col(WordInflection::Kind)
+                    .col(WordInflection::Form)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        // 按变形反查原形
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-word_inflection-form")
+                    .table(WordInflection::Table)
+                    .col(WordInflection::Form)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WordInflection::Table).to_owned())
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MasterWord::Table)
+                    .drop_column(MasterWord::PronunciationUk)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MasterWord::Table)
+                    .drop_column(MasterWord::PronunciationUs)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum MasterWord {
+    Table,
+    Id,
+    PronunciationUs,
+    PronunciationUk,
+}
+
+#[derive(DeriveIden)]
+enum WordInflection {
+    Table,
+    Id,
+    MasterWordId,
+    Form, // 变形后的拼写，统一小写
+    Kind, // 变形种类，比如 "past"、"plural"
+}
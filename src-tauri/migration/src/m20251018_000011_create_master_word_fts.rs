@@ -0,0 +1,58 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 单词和释义的全文索引 (FTS5)，内容不另存，直接引用 master_word 表
+// unicode61 按空格和标点分词，英文前缀查询和整词的中文释义都能命中
+const UP: [&str; 5] = [
+    "CREATE VIRTUAL TABLE IF NOT EXISTS master_word_fts USING fts5(
+        text, definition,
+        content = 'master_word', content_rowid = 'id',
+        tokenize = 'unicode61 remove_diacritics 2'
+    )",
+    // 用触发器让索引跟着 master_word 变
+    "CREATE TRIGGER IF NOT EXISTS master_word_fts_insert AFTER INSERT ON master_word BEGIN
+        INSERT INTO master_word_fts (rowid, text, definition)
+        VALUES (new.id, new.text, new.definition);
+    END",
+    "CREATE TRIGGER IF NOT EXISTS master_word_fts_delete AFTER DELETE ON master_word BEGIN
+        INSERT INTO master_word_fts (master_word_fts, rowid, text, definition)
+        VALUES ('delete', old.id, old.text, old.definition);
+    END",
+    "CREATE TRIGGER IF NOT EXISTS master_word_fts_update
+    AFTER UPDATE OF text, definition ON master_word BEGIN
+        INSERT INTO master_word_fts (master_word_fts, rowid, text, definition)
+        VALUES ('delete', old.id, old.text, old.definition);
+        INSERT INTO master_word_fts (rowid, text, definition)
+        VALUES (new.id, new.text, new.definition);
+    END",
+    // 已有的单词建一次索引
+    "INSERT INTO master_word_fts (master_word_fts) VALUES ('rebuild')",
+];
+
+const DOWN: [&str; 4] = [
+    "DROP TRIGGER IF EXISTS master_word_fts_update",
+    "DROP TRIGGER IF EXISTS master_word_fts_delete",
+    "DROP TRIGGER IF EXISTS master_word_fts_insert",
+    "DROP TABLE IF EXISTS master_word_fts",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in UP {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in DOWN {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 释义的拼音 (不带声调)，用来按拼音搜索中文释义
+        // 迁移里算不了拼音，老数据启动时再补
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WordSense::Table)
+                    .add_column(ColumnDef::new(WordSense::Pinyin).string())
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WordSense::Table)
+                    .drop_column(WordSense::Pinyin)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum WordSense {
+    Table,
+    Pinyin,
+}
@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 暂停 / 搁置前的状态，恢复时回到这个状态
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserWord::Table)
+                    .add_column(
+                        ColumnDef::new(UserWord::ResumeStatus)
+                            .small_integer()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserWord::Table)
+                    .drop_column(UserWord::ResumeStatus)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserWord {
+    Table,
+    ResumeStatus,
+}
@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 内置词书里的单词数，安装时写入，列出词书时不用再读词书内容
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WordBook::Table)
+                    .add_column(ColumnDef::new(WordBook::WordCount).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 已经装过的词书按现有的单词数补上
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE word_book SET word_count = (
+                    SELECT COUNT(*) FROM word_book_membership
+                    WHERE word_book_membership.word_book_id = word_book.id
+                )
+                WHERE id IN (SELECT word_book_id FROM word_book_membership)",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WordBook::Table)
+                    .drop_column(WordBook::WordCount)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WordBook {
+    Table,
+    WordCount,
+}
@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 释义的 trigram 全文索引：中文没有空格，unicode61 会把 "红苹果" 当成一个词，
+// 搜 "苹果" 就找不到；trigram 按三个字一组建索引，LIKE '%苹果%' 也能匹配到中间
+// 三个字以上的关键词走索引，更短的退回逐行比较，结果一样只是慢一些
+const UP: [&str; 5] = [
+    "CREATE VIRTUAL TABLE IF NOT EXISTS master_word_definition_fts USING fts5(
+        definition,
+        content = 'master_word', content_rowid = 'id',
+        tokenize = 'trigram'
+    )",
+    "CREATE TRIGGER IF NOT EXISTS master_word_definition_fts_insert
+    AFTER INSERT ON master_word BEGIN
+        INSERT INTO master_word_definition_fts (rowid, definition)
+        VALUES (new.id, new.definition);
+    END",
+    "CREATE TRIGGER IF NOT EXISTS master_word_definition_fts_delete
+    AFTER DELETE ON master_word BEGIN
+        INSERT INTO master_word_definition_fts (master_word_definition_fts, rowid, definition)
+        VALUES ('delete', old.id, old.definition);
+    END",
+    "CREATE TRIGGER IF NOT EXISTS master_word_definition_fts_update
+    AFTER UPDATE OF definition ON master_word BEGIN
+        INSERT INTO master_word_definition_fts (master_word_definition_fts, rowid, definition)
+        VALUES ('delete', old.id, old.definition);
+        INSERT INTO master_word_definition_fts (rowid, definition)
+        VALUES (new.id, new.definition);
+    END",
+    "INSERT INTO master_word_definition_fts (master_word_definition_fts) VALUES ('rebuild')",
+];
+
+const DOWN: [&str; 4] = [
+    "DROP TRIGGER IF EXISTS master_word_definition_fts_update",
+    "DROP TRIGGER IF EXISTS master_word_definition_fts_delete",
+    "DROP TRIGGER IF EXISTS master_word_definition_fts_insert",
+    "DROP TABLE IF EXISTS master_word_definition_fts",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in UP {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in DOWN {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+}
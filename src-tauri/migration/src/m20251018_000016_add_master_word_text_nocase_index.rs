@@ -0,0 +1,23 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 按单词查找不分大小写 (text = ? COLLATE NOCASE)，索引也要用 NOCASE 排序规则才能用上
+const UP: &str = "CREATE INDEX IF NOT EXISTS \"idx-master_word-text-nocase\" \
+                  ON master_word (text COLLATE NOCASE)";
+
+const DOWN: &str = "DROP INDEX IF EXISTS \"idx-master_word-text-nocase\"";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.get_connection().execute_unprepared(UP).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.get_connection().execute_unprepared(DOWN).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 单词列表按 (text, id) 或 (frequency_rank, text, id) 分页，按索引顺序往后读，不用每页排序
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-master_word-text-id")
+                    .table(MasterWord::Table)
+                    .col(MasterWord::Text)
+                    .col(MasterWord::Id)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-master_word-frequency_rank-text-id")
+                    .table(MasterWord::Table)
+                    .col(MasterWord::FrequencyRank)
+                    .col(MasterWord::Text)
+                    .col(MasterWord::Id)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-master_word-frequency_rank-text-id")
+                    .table(MasterWord::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-master_word-text-id")
+                    .table(MasterWord::Table)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum MasterWord {
+    Table,
+    Id,
+    Text,
+    FrequencyRank,
+}
@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 老数据里同一个单词可能有几条学习进度：留下最近复习过的那条 (一样时留复习记录多的)，
+// 其余几条的复习记录改挂到留下的那条上，再删掉多余的进度，复习历史不会丢
+const MERGE_DUPLICATES: [&str; 4] = [
+    "CREATE TEMP TABLE user_word_duplicate AS
+    SELECT id, keep_id FROM (
+        SELECT id, FIRST_VALUE(id) OVER (
+            PARTITION BY master_word_id
+            ORDER BY last_review IS NULL, last_review DESC,
+                (SELECT COUNT(*) FROM review_log WHERE review_log.user_word_id = user_word.id) DESC,
+                id
+        ) AS keep_id
+        FROM user_word
+    ) WHERE id <> keep_id",
+    "UPDATE review_log SET user_word_id = (
+        SELECT keep_id FROM user_word_duplicate WHERE user_word_duplicate.id = review_log.user_word_id
+    ) WHERE user_word_id IN (SELECT id FROM user_word_duplicate)",
+    "DELETE FROM user_word WHERE id IN (SELECT id FROM user_word_duplicate)",
+    "DROP TABLE user_word_duplicate",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        for sql in MERGE_DUPLICATES {
+            db.execute_unprepared(sql).await?;
+        }
+        // 一个单词只有一条学习进度，单词列表按一对一联表查询
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-user_word-master_word_id")
+                    .table(UserWord::Table)
+                    .col(UserWord::MasterWordId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-user_word-master_word_id")
+                    .table(UserWord::Table)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserWord {
+    Table,
+    MasterWordId,
+}
@@ -0,0 +1,21 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 释义的拼音改成音节之间用空格隔开 ("ping guo")，搜索时只在音节边界上匹配
+// 迁移里算不了拼音，清空后启动时按新格式重新补；回滚时同样清空，让旧版本按旧格式补
+const RESET: &str = "UPDATE word_sense SET pinyin = NULL";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.get_connection().execute_unprepared(RESET).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.get_connection().execute_unprepared(RESET).await?;
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
 use chrono::{DateTime, Duration, Utc};
-#[derive(Debug, Clone, Copy)]
+
+use crate::entities::card_state::CardState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Rating {
     Again = 1,
     Hard = 2,
@@ -19,63 +22,433 @@ impl Rating {
     }
 }
 
+// FSRS-5 默认参数 w[0] ~ w[18]
+// 来自 open-spaced-repetition 在大量复习记录上拟合的结果
+pub const DEFAULT_PARAMS: [f32; 19] = [
+    0.40255, 1.18385, 3.173, 15.69105, 7.1949, 0.5345, 1.4604, 0.0046, 1.54575, 0.1192, 1.01925,
+    1.9395, 0.11, 0.29605, 2.2698, 0.2315, 2.9898, 0.51655, 0.6621,
+];
+
+// 遗忘曲线的衰减指数和系数，保证 R(S, S) = 0.9
+const DECAY: f32 = -0.5;
+const FACTOR: f32 = 19.0 / 81.0;
+
+// 默认目标记忆保持率
+pub const DEFAULT_DESIRED_RETENTION: f32 = 0.9;
+
+const MIN_STABILITY: f32 = 0.01;
+const MAX_INTERVAL_DAYS: f32 = 36500.0;
+
+// 默认学习步骤 1 分钟 / 10 分钟，重学步骤 10 分钟
+pub const DEFAULT_LEARNING_STEPS: [u32; 2] = [1, 10];
+pub const DEFAULT_RELEARNING_STEPS: [u32; 1] = [10];
+
+// 排期用到的配置：参数向量 + 目标保持率 + 学习步骤 (分钟)
+#[derive(Debug, Clone)]
+pub struct SchedulerConfig {
+    pub params: [f32; 19],
+    pub desired_retention: f32,
+    pub learning_steps: Vec<u32>,
+    pub relearning_steps: Vec<u32>,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            params: DEFAULT_PARAMS,
+            desired_retention: DEFAULT_DESIRED_RETENTION,
+            learning_steps: DEFAULT_LEARNING_STEPS.to_vec(),
+            relearning_steps: DEFAULT_RELEARNING_STEPS.to_vec(),
+        }
+    }
+}
+
+// 单词当前的调度状态
+pub struct CardMemory {
+    pub stability: f32,
+    pub difficulty: f32,
+    pub last_review: Option<DateTime<Utc>>,
+    pub state: CardState,
+    pub step: i16, // 学习 / 重学步骤的序号
+}
+
 // 算法的输入输出结构
 pub struct ReviewResult {
     pub new_stability: f32,
     pub new_difficulty: f32,
+    pub new_state: CardState,
+    pub new_step: i16,
     pub next_due: DateTime<Utc>,
+    pub elapsed_days: f32,   // 距离上次复习过去的天数
+    pub scheduled_days: f32, // 本次安排的复习间隔 (天)
 }
 
-//计算函数
+// 可提取率：距离上次复习 elapsed_days 天后还能记起来的概率
+pub fn retrievability(elapsed_days: f32, stability: f32) -> f32 {
+    (1.0 + FACTOR * elapsed_days / stability).powf(DECAY)
+}
 
-pub fn calculate_next_review(
-    current_stability: f32,
-    current_difficulty: f32,
+// 在目标保持率下，稳定性对应的复习间隔 (天)
+pub fn next_interval(stability: f32, desired_retention: f32) -> f32 {
+    let interval = stability / FACTOR * (desired_retention.powf(1.0 / DECAY) - 1.0);
+    interval.clamp(1.0, MAX_INTERVAL_DAYS)
+}
+
+fn clamp_difficulty(d: f32) -> f32 {
+    d.clamp(1.0, 10.0)
+}
+
+// 新单词第一次评分时的初始稳定性
+pub fn initial_stability(w: &[f32; 19], rating: Rating) -> f32 {
+    w[rating as usize - 1].max(MIN_STABILITY)
+}
+
+// 新单词第一次评分时的初始难度
+pub fn initial_difficulty(w: &[f32; 19], rating: Rating) -> f32 {
+    clamp_difficulty(w[4] - (w[5] * (rating as i32 - 1) as f32).exp() + 1.0)
+}
+
+// 难度更新：线性阻尼 + 向 "Easy 初始难度" 均值回归
+pub fn next_difficulty(w: &[f32; 19], difficulty: f32, rating: Rating) -> f32 {
+    let delta = -w[6] * (rating as i32 - 3) as f32;
+    let damped = difficulty + delta * (10.0 - difficulty) / 9.0;
+    let reverted = w[7] * initial_difficulty(w, Rating::Easy) + (1.0 - w[7]) * damped;
+    clamp_difficulty(reverted)
+}
+
+// 记住了 (Hard/Good/Easy) 之后的稳定性
+pub fn next_recall_stability(
+    w: &[f32; 19],
+    difficulty: f32,
+    stability: f32,
+    retrievability: f32,
     rating: Rating,
-) -> ReviewResult {
-    let mut s = current_stability;
-    let mut d = current_difficulty;
-
-    // 1. 更新难度 (Difficulty)
-    // 简单的线性调整：答错加难度，答对减难度
-    let d_delta = match rating {
-        Rating::Again => 0.4,
-        Rating::Hard => 0.2,
-        Rating::Good => -0.1,
-        Rating::Easy => -0.3,
+) -> f32 {
+    let hard_penalty = if let Rating::Hard = rating {
+        w[15]
+    } else {
+        1.0
+    };
+    let easy_bonus = if let Rating::Easy = rating {
+        w[16]
+    } else {
+        1.0
     };
 
-    d = (d + d_delta).max(1.0).min(10.0); // 限制范围 1.0 ~ 10.0
+    stability
+        * (w[8].exp()
+            * (11.0 - difficulty)
+            * stability.powf(-w[9])
+            * ((w[10] * (1.0 - retrievability)).exp() - 1.0)
+            * hard_penalty
+            * easy_bonus
+            + 1.0)
+}
+
+// 忘记了 (Again) 之后的稳定性，不会超过遗忘前的稳定性
+pub fn next_forget_stability(
+    w: &[f32; 19],
+    difficulty: f32,
+    stability: f32,
+    retrievability: f32,
+) -> f32 {
+    let s = w[11]
+        * difficulty.powf(-w[12])
+        * ((stability + 1.0).powf(w[13]) - 1.0)
+        * (w[14] * (1.0 - retrievability)).exp();
+    s.min(stability).max(MIN_STABILITY)
+}
 
-    // 2. 更新稳定性 (Stability) - 也就是下一次复习的间隔天数
+// 同一天内再次复习 (间隔不足一天) 时的稳定性: S * e^(w17 * (G - 3 + w18))
+pub fn short_term_stability(w: &[f32; 19], stability: f32, rating: Rating) -> f32 {
+    (stability * (w[17] * ((rating as i32 - 3) as f32 + w[18])).exp()).max(MIN_STABILITY)
+}
 
-    if s == 0.0 {
-        //新单词
-        s = match rating {
-            Rating::Again => 0.1, //马上复习
-            Rating::Hard => 1.0,  //一天
-            Rating::Good => 3.0,  //三天
-            Rating::Easy => 7.0,  //7天
-        };
+// 一次评分后的记忆状态 (稳定性, 难度)
+// stability <= 0 代表新单词，还没有记忆状态
+pub fn next_memory_state(
+    w: &[f32; 19],
+    stability: f32,
+    difficulty: f32,
+    elapsed_days: f32,
+    rating: Rating,
+) -> (f32, f32) {
+    if stability <= 0.0 {
+        return (initial_stability(w, rating), initial_difficulty(w, rating));
+    }
+
+    let d = next_difficulty(w, difficulty, rating);
+    let s = if elapsed_days < 1.0 {
+        short_term_stability(w, stability, rating)
     } else {
+        let r = retrievability(elapsed_days, stability);
         match rating {
-            Rating::Again => s = 0.5,
-            Rating::Hard => s = s * 1.2,
-            Rating::Good => s = s * (1.0 + (11.0 - d) / 5.0), // 正常增长，难度越低增长越快
-            Rating::Easy => s = s * (1.0 + (11.0 - d) / 3.0), // 快速增长
+            Rating::Again => next_forget_stability(w, difficulty, stability, r),
+            _ => next_recall_stability(w, difficulty, stability, r, rating),
+        }
+    };
+    (s, d)
+}
+
+// 学习步骤的走法
+enum StepOutcome {
+    Step(i16, u32), // 停在第几步，多少分钟后再来
+    Graduate,       // 走完步骤，进入 Review
+}
+
+fn next_step(steps: &[u32], step: i16, rating: Rating) -> StepOutcome {
+    if steps.is_empty() {
+        return StepOutcome::Graduate;
+    }
+    let step = (step.max(0) as usize).min(steps.len() - 1);
+    match rating {
+        Rating::Again => StepOutcome::Step(0, steps[0]),
+        Rating::Hard => StepOutcome::Step(step as i16, steps[step]), // 原地重复当前步骤
+        Rating::Good if step + 1 < steps.len() => {
+            StepOutcome::Step(step as i16 + 1, steps[step + 1])
         }
+        Rating::Good | Rating::Easy => StepOutcome::Graduate,
     }
+}
+
+//计算函数
 
-    //修改忘记算法
-    let next_due = if let Rating::Again = rating {
-        Utc::now() + Duration::seconds(3)
+pub fn calculate_next_review(
+    config: &SchedulerConfig,
+    card: &CardMemory,
+    rating: Rating,
+    now: DateTime<Utc>,
+) -> ReviewResult {
+    let elapsed_days = card
+        .last_review
+        .map(|t| (now - t).num_seconds().max(0) as f32 / 86400.0)
+        .unwrap_or(0.0);
+
+    // 没有复习过的单词一律按新单词处理
+    let stability = if card.last_review.is_some() {
+        card.stability
     } else {
-        Utc::now() + Duration::minutes((s * 24.0 * 60.0) as i64)
+        0.0
+    };
+    let (s, d) = next_memory_state(
+        &config.params,
+        stability,
+        card.difficulty,
+        elapsed_days,
+        rating,
+    );
+
+    // 状态机：学习 / 重学步骤内按分钟排期，毕业后按 FSRS 间隔排期
+    let outcome = match card.state {
+        CardState::New => next_step(&config.learning_steps, 0, rating),
+        CardState::Learning => next_step(&config.learning_steps, card.step, rating),
+        CardState::Relearning => next_step(&config.relearning_steps, card.step, rating),
+        CardState::Review => match (rating, config.relearning_steps.first()) {
+            (Rating::Again, Some(&minutes)) => StepOutcome::Step(0, minutes),
+            _ => StepOutcome::Graduate,
+        },
+        // 暂停 / 搁置的单词由调用方拦截，这里按 Review 处理
+        CardState::Suspended | CardState::Buried => StepOutcome::Graduate,
+    };
+
+    let (new_state, new_step, next_due, scheduled_days) = match outcome {
+        StepOutcome::Step(step, minutes) => {
+            let state = match card.state {
+                CardState::Review | CardState::Relearning => CardState::Relearning,
+                _ => CardState::Learning,
+            };
+            (
+                state,
+                step,
+                now + Duration::minutes(minutes as i64),
+                minutes as f32 / 1440.0,
+            )
+        }
+        StepOutcome::Graduate => {
+            let interval_days = next_interval(s, config.desired_retention).round();
+            (
+                CardState::Review,
+                0,
+                now + Duration::days(interval_days as i64),
+                interval_days,
+            )
+        }
     };
 
     ReviewResult {
         new_stability: s,
         new_difficulty: d,
+        new_state,
+        new_step,
         next_due,
+        elapsed_days,
+        scheduled_days,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, FixedClock};
+    use chrono::TimeZone;
+
+    fn clock() -> FixedClock {
+        FixedClock(Utc.with_ymd_and_hms(2025, 3, 1, 10, 0, 0).unwrap())
+    }
+
+    fn new_card() -> CardMemory {
+        CardMemory {
+            stability: 0.0,
+            difficulty: 0.0,
+            last_review: None,
+            state: CardState::New,
+            step: 0,
+        }
+    }
+
+    // 按评分结果推进一张卡片
+    fn advance(result: &ReviewResult, now: DateTime<Utc>) -> CardMemory {
+        CardMemory {
+            stability: result.new_stability,
+            difficulty: result.new_difficulty,
+            last_review: Some(now),
+            state: result.new_state,
+            step: result.new_step,
+        }
+    }
+
+    #[test]
+    fn retention_at_stability_is_ninety_percent() {
+        assert!((retrievability(10.0, 10.0) - 0.9).abs() < 1e-4);
+        assert!((next_interval(10.0, 0.9) - 10.0).abs() < 1e-3);
+        // 目标保持率越高，间隔越短
+        assert!(next_interval(10.0, 0.95) < next_interval(10.0, 0.85));
+    }
+
+    #[test]
+    fn short_term_stability_matches_reference() {
+        // 参考实现: S * exp(w17 * (G - 3 + w18))
+        let again = short_term_stability(&DEFAULT_PARAMS, 10.0, Rating::Again);
+        let good = short_term_stability(&DEFAULT_PARAMS, 10.0, Rating::Good);
+        assert!((again - 5.0103).abs() < 1e-3, "again = {}", again);
+        assert!((good - 14.0777).abs() < 1e-3, "good = {}", good);
+    }
+
+    #[test]
+    fn new_card_walks_through_learning_steps() {
+        let config = SchedulerConfig::default();
+        let now = clock().now();
+
+        let first = calculate_next_review(&config, &new_card(), Rating::Good, now);
+        assert_eq!(first.new_state, CardState::Learning);
+        assert_eq!(first.new_step, 1);
+        assert_eq!(first.next_due, now + Duration::minutes(10));
+
+        let later = now + Duration::minutes(10);
+        let card = advance(&first, now);
+        let second = calculate_next_review(&config, &card, Rating::Good, later);
+        assert_eq!(second.new_state, CardState::Review);
+        assert_eq!(second.new_step, 0);
+        assert!(second.next_due >= later + Duration::days(1));
+        assert_eq!(
+            (second.next_due - later).num_days() as f32,
+            second.scheduled_days
+        );
+    }
+
+    #[test]
+    fn again_and_hard_stay_in_learning() {
+        let config = SchedulerConfig::default();
+        let now = clock().now();
+        let card = CardMemory {
+            stability: 3.0,
+            difficulty: 5.0,
+            last_review: Some(now - Duration::minutes(1)),
+            state: CardState::Learning,
+            step: 1,
+        };
+
+        let again = calculate_next_review(&config, &card, Rating::Again, now);
+        assert_eq!((again.new_state, again.new_step), (CardState::Learning, 0));
+        assert_eq!(again.next_due, now + Duration::minutes(1));
+
+        let hard = calculate_next_review(&config, &card, Rating::Hard, now);
+        assert_eq!((hard.new_state, hard.new_step), (CardState::Learning, 1));
+        assert_eq!(hard.next_due, now + Duration::minutes(10));
+    }
+
+    #[test]
+    fn easy_skips_learning_steps() {
+        let config = SchedulerConfig::default();
+        let now = clock().now();
+        let easy = calculate_next_review(&config, &new_card(), Rating::Easy, now);
+        assert_eq!(easy.new_state, CardState::Review);
+        let good = calculate_next_review(&config, &new_card(), Rating::Good, now);
+        assert!(easy.new_stability > good.new_stability);
+        assert!(easy.new_difficulty < good.new_difficulty);
+    }
+
+    #[test]
+    fn forgotten_review_card_relearns_and_comes_back() {
+        let config = SchedulerConfig::default();
+        let now = clock().now();
+        let card = CardMemory {
+            stability: 20.0,
+            difficulty: 5.0,
+            last_review: Some(now - Duration::days(20)),
+            state: CardState::Review,
+            step: 0,
+        };
+
+        let lapse = calculate_next_review(&config, &card, Rating::Again, now);
+        assert_eq!(
+            (lapse.new_state, lapse.new_step),
+            (CardState::Relearning, 0)
+        );
+        assert_eq!(lapse.next_due, now + Duration::minutes(10));
+        assert!(lapse.new_stability < card.stability);
+        assert!(lapse.new_difficulty > card.difficulty);
+
+        let later = now + Duration::minutes(10);
+        let relearned = calculate_next_review(&config, &advance(&lapse, now), Rating::Good, later);
+        assert_eq!(relearned.new_state, CardState::Review);
+        assert!(relearned.next_due >= later + Duration::days(1));
+    }
+
+    #[test]
+    fn recalled_review_card_grows_interval() {
+        let config = SchedulerConfig::default();
+        let now = clock().now();
+        let card = CardMemory {
+            stability: 10.0,
+            difficulty: 5.0,
+            last_review: Some(now - Duration::days(10)),
+            state: CardState::Review,
+            step: 0,
+        };
+
+        let hard = calculate_next_review(&config, &card, Rating::Hard, now);
+        let good = calculate_next_review(&config, &card, Rating::Good, now);
+        let easy = calculate_next_review(&config, &card, Rating::Easy, now);
+        assert!(good.new_stability > card.stability);
+        assert!(hard.scheduled_days <= good.scheduled_days);
+        assert!(good.scheduled_days <= easy.scheduled_days);
+        assert_eq!(good.elapsed_days, 10.0);
+    }
+
+    #[test]
+    fn empty_steps_graduate_immediately() {
+        let config = SchedulerConfig {
+            learning_steps: vec![],
+            relearning_steps: vec![],
+            ..Default::default()
+        };
+        let now = clock().now();
+        let first = calculate_next_review(&config, &new_card(), Rating::Again, now);
+        assert_eq!(first.new_state, CardState::Review);
+
+        let card = advance(&first, now);
+        let lapse = calculate_next_review(&config, &card, Rating::Again, now + Duration::days(2));
+        assert_eq!(lapse.new_state, CardState::Review);
     }
 }
@@ -0,0 +1,149 @@
+use sea_orm::DbErr;
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard, OnceLock},
+};
+
+use crate::{db::JsonWord, entities::source_enum::Source};
+
+pub struct BookDef {
+    pub source: Source,
+    pub name: &'static str,
+    path: &'static str, // 随安装包放在资源目录，用到时才读取
+}
+
+// 内置词书，每个来源一本；Custom / Other 是用户自己导入的，没有内置词书
+// 词表还没整理好的词书照样列出来，资源目录里没有文件时标成不可用
+pub const BOOKS: [BookDef; 5] = [
+    BookDef {
+        source: Source::ElementarySchool,
+        name: "小学",
+        path: "resources/books/elementary_school_words.json",
+    },
+    BookDef {
+        source: Source::JuniorHighSchool,
+        name: "初中",
+        path: "resources/books/junior_high_school_words.json",
+    },
+    BookDef {
+        source: Source::HighSchool,
+        name: "高中",
+        path: "resources/books/high_school_words.json",
+    },
+    BookDef {
+        source: Source::Cet4,
+        name: "大学英语四级",
+        path: "resources/books/cet4_words.json",
+    },
+    BookDef {
+        source: Source::Cet6,
+        name: "大学英语六级",
+        path: "resources/books/cet6_words.json",
+    },
+];
+
+// 内置词频表：一行一个单词，最常用的在最前面，行里单词后面的内容 (比如出现次数) 忽略
+const FREQUENCY_LIST: &str = "resources/frequency/word_frequency.txt";
+
+pub fn find_book(source: &Source) -> Option<&'static BookDef> {
+    BOOKS.iter().find(|b| &b.source == source)
+}
+
+type BookCache = HashMap<Source, Arc<Vec<JsonWord>>>;
+
+// 词书注册表：词书内容第一次用到时才解析，之后缓存在内存里
+pub struct BookRegistry {
+    resource_dir: PathBuf,
+    cache: Mutex<BookCache>,
+    frequency: OnceLock<Option<Arc<FrequencyList>>>,
+}
+
+impl BookRegistry {
+    pub fn new(resource_dir: PathBuf) -> Self {
+        Self {
+            resource_dir,
+            cache: Mutex::new(HashMap::new()),
+            frequency: OnceLock::new(),
+        }
+    }
+
+    // 资源文件不在安装包里的词书不可用
+    pub fn is_available(&self, book: &BookDef) -> bool {
+        self.resource_dir.join(book.path).is_file()
+    }
+
+    fn cache(&self) -> Result<MutexGuard<'_, BookCache>, DbErr> {
+        self.cache
+            .lock()
+            .map_err(|_| DbErr::Custom("词书缓存不可用".to_owned()))
+    }
+
+    pub(crate) fn load(&self, source: &Source) -> Result<Arc<Vec<JsonWord>>, DbErr> {
+        if let Some(words) = self.cache()?.get(source) {
+            return Ok(words.clone());
+        }
+
+        let book = find_book(source)
+            .ok_or_else(|| DbErr::Custom(format!("没有内置的 {} 词书", source.as_ref())))?;
+        if !self.is_available(book) {
+            return Err(DbErr::Custom(format!("安装包里还没有{}词书", book.name)));
+        }
+        let json = fs::read_to_string(self.resource_dir.join(book.path))
+            .map_err(|e| DbErr::Custom(format!("无法读取{}词书: {}", book.name, e)))?;
+        let words: Vec<JsonWord> = serde_json::from_str(&json)
+            .map_err(|e| DbErr::Custom(format!("{}词书格式错误: {}", book.name, e)))?;
+
+        let words = Arc::new(words);
+        self.cache()?.insert(source.clone(), words.clone());
+        Ok(words)
+    }
+
+    // 内置词频表，安装包里没有时返回 None (只提示一次)
+    pub(crate) fn frequency_list(&self) -> Option<Arc<FrequencyList>> {
+        self.frequency
+            .get_or_init(|| {
+                let path = self.resource_dir.join(FREQUENCY_LIST);
+                match fs::read_to_string(&path) {
+                    Ok(text) => Some(Arc::new(FrequencyList::parse(&text))),
+                    Err(e) => {
+                        eprintln!("⚠️ 无法读取词频表 {}: {}", path.display(), e);
+                        None
+                    }
+                }
+            })
+            .clone()
+    }
+}
+
+// 词频表：小写单词 -> 词频排名 (从 1 开始)
+pub struct FrequencyList {
+    pub ranks: HashMap<String, i32>,
+    pub fingerprint: String, // 词频表内容的指纹，内容变了才需要重新计算排名
+}
+
+impl FrequencyList {
+    fn parse(text: &str) -> Self {
+        let mut ranks = HashMap::new();
+        let words = text
+            .lines()
+            .filter(|l| !l.trim_start().starts_with('#'))
+            .filter_map(|l| l.trim().split([' ', '\t', ',']).next())
+            .map(|w| w.trim().to_lowercase())
+            .filter(|w| !w.is_empty());
+        for word in words {
+            let rank = ranks.len() as i32 + 1;
+            ranks.entry(word).or_insert(rank);
+        }
+
+        // FNV-1a，跨版本稳定，够用来判断文件有没有变
+        let hash = text.bytes().fold(0xcbf29ce484222325u64, |h, b| {
+            (h ^ b as u64).wrapping_mul(0x100000001b3)
+        });
+        Self {
+            ranks,
+            fingerprint: format!("{:016x}", hash),
+        }
+    }
+}
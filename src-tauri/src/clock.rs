@@ -0,0 +1,91 @@
+use chrono::{DateTime, Duration, Utc};
+use std::sync::RwLock;
+
+// 时间来源，排期和查询都从这里取 "现在"，方便测试和预览
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+// 固定时间，测试用
+#[cfg(test)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+#[cfg(test)]
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+// 在系统时间上加一个固定偏移，比如 "明天的这个时候"，测试用
+// 应用里的预览由 AppClock 负责，它的偏移可以随时改和清掉
+#[cfg(test)]
+pub struct OffsetClock(pub Duration);
+
+#[cfg(test)]
+impl Clock for OffsetClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now() + self.0
+    }
+}
+
+// 放进 Tauri State 的时钟，平时就是系统时间
+// 开启预览后整体偏移到预览日期，时间照常流逝；预览期间只能查看，不能修改学习进度
+#[derive(Default)]
+pub struct AppClock {
+    offset: RwLock<Duration>,
+}
+
+impl AppClock {
+    // 设置预览时间，None 代表回到真实时间
+    pub fn set_preview(&self, preview: Option<DateTime<Utc>>) {
+        let offset = preview
+            .map(|t| t - Utc::now())
+            .unwrap_or_else(Duration::zero);
+        if let Ok(mut guard) = self.offset.write() {
+            *guard = offset;
+        }
+    }
+
+    pub fn is_preview(&self) -> bool {
+        self.offset.read().map(|o| !o.is_zero()).unwrap_or(false)
+    }
+}
+
+impl Clock for AppClock {
+    fn now(&self) -> DateTime<Utc> {
+        let offset = self
+            .offset
+            .read()
+            .map(|o| *o)
+            .unwrap_or_else(|_| Duration::zero());
+        Utc::now() + offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preview_shifts_now_and_can_be_cleared() {
+        let clock = AppClock::default();
+        assert!(!clock.is_preview());
+
+        let target = Utc::now() + Duration::days(3);
+        clock.set_preview(Some(target));
+        assert!(clock.is_preview());
+        assert!((clock.now() - target).num_seconds().abs() <= 1);
+
+        clock.set_preview(None);
+        assert!(!clock.is_preview());
+        assert!((clock.now() - Utc::now()).num_seconds().abs() <= 1);
+    }
+
+    #[test]
+    fn offset_clock_runs_ahead_of_system_time() {
+        let clock = OffsetClock(Duration::days(1));
+        let ahead = clock.now() - Utc::now();
+        assert!((ahead - Duration::days(1)).num_seconds().abs() <= 1);
+    }
+}
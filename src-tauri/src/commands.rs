@@ -1,66 +1,159 @@
+use chrono::{DateTime, Utc};
 use sea_orm::DatabaseConnection;
-use tauri::State;
+use std::{
+    io::{BufReader, Write},
+    path::PathBuf,
+};
+use tauri::{path::BaseDirectory, AppHandle, Emitter, Manager, State};
+use tauri_plugin_fs::{FsExt, OpenOptions};
 
 use crate::{
-    db,
-    entities::{master_word, user_word},
+    algorithm::Rating,
+    books::BookRegistry,
+    clock::{AppClock, Clock},
+    entities::{card_state::CardState, review_log, source_enum::Source},
     error::ApiError,
-    models::ReviewCard,
+    models::{ReviewCard, WordEntry},
     services::{
-        master_word_service,
-        stats_service::{self, DashboardStats},
+        anki_import_service::{self, AnkiImportOptions, AnkiImportReport},
+        book_service::{self, BookInfo},
+        dictionary_service::{self, DictionaryFinished},
+        export_service::{self, ExportFilter, ExportFormat},
+        fsrs_params_service::{self, OptimizeReport},
+        import_service::{self, ImportFormat, ImportReport},
+        master_word_service::{
+            self, SearchResult, WordCursor, WordListFilter, WordLookup, WordPage, WordSort,
+        },
+        progress_service::{self, ProgressImportReport},
+        review_log_service,
+        settings_service::{self, NewWordOrder, Settings},
+        stats_service::{self, DashboardStats, WorkloadForecast},
         user_word_service,
     },
 };
 
-//一次性请求所有
+// 预览模式下时间是假的，写进去的复习时间会污染真实数据，只允许查看
+fn reject_preview(clock: &AppClock) -> Result<(), ApiError> {
+    if clock.is_preview() {
+        return Err(ApiError::InvalidInput(
+            "预览模式下不能修改学习进度，请先回到当前日期".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+// 词频表没有随安装包提供时，按词频排序 / 筛选 / 选新词都没有意义，直接拒绝
+fn require_frequency_list(registry: &BookRegistry) -> Result<(), ApiError> {
+    if registry.frequency_list().is_none() {
+        return Err(ApiError::InvalidInput(
+            "安装包里没有词频表，不能按词频排序或筛选".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+//一次性请求所有，book 不传时是整个词库
 #[tauri::command]
 pub async fn get_all_master_words(
     db: State<'_, DatabaseConnection>,
-) -> Result<Vec<master_word::Model>, ApiError> {
-    let words = master_word_service::get_all_master_words(&db).await?;
+    book: Option<Source>,
+) -> Result<Vec<WordEntry>, ApiError> {
+    let words = master_word_service::get_all_master_words(&db, book).await?;
 
     Ok(words)
 }
 
-//按首字母查找
+//按首字母查找，带上学习进度；states 不传时不按学习状态筛选
 #[tauri::command]
 pub async fn get_master_word_by_first_letter(
     db: State<'_, DatabaseConnection>,
     letter: Option<String>,
-) -> Result<Vec<master_word::Model>, ApiError> {
-    let words = master_word_service::get_master_word_by_first_letter(&db, letter).await?;
+    book: Option<Source>,
+    states: Option<Vec<CardState>>,
+) -> Result<Vec<WordEntry>, ApiError> {
+    let words = master_word_service::get_master_word_by_first_letter(
+        &db,
+        letter,
+        book,
+        states.unwrap_or_default(),
+    )
+    .await?;
     Ok(words)
 }
 
-//分页查找
+//分页查找，cursor 用上一页返回的 next_cursor，第一页不传
 #[tauri::command]
 pub async fn get_words_list_filiter(
     db: State<'_, DatabaseConnection>,
-    page: u64,
+    registry: State<'_, BookRegistry>,
+    filter: Option<WordListFilter>,
+    cursor: Option<String>,
     limit: u64,
-) -> Result<Vec<master_word::Model>, ApiError> {
-    let words = master_word_service::get_words_paginated(&db, page, limit).await?;
-    Ok(words)
+) -> Result<WordPage, ApiError> {
+    if limit == 0 {
+        return Err(ApiError::InvalidInput("每页至少要有一个单词".to_owned()));
+    }
+    let filter = filter.unwrap_or_default();
+    if filter.sort == WordSort::Frequency || filter.max_rank.is_some() {
+        require_frequency_list(&registry)?;
+    }
+    let cursor = cursor
+        .map(|c| {
+            WordCursor::parse(&c).ok_or_else(|| ApiError::InvalidInput("无效的分页位置".to_owned()))
+        })
+        .transpose()?;
+    let page = master_word_service::get_words_paginated(&db, &filter, cursor, limit).await?;
+    Ok(page)
 }
 
-//搜索api
+//搜索api，page 从 0 开始
 #[tauri::command]
+#[allow(clippy::too_many_arguments)] // 参数和前端的调用一一对应
 pub async fn search_master_words(
     db: State<'_, DatabaseConnection>,
+    registry: State<'_, BookRegistry>,
     keyword: String,
-) -> Result<Vec<master_word::Model>, ApiError> {
-    let words = master_word_service::search_words(&db, keyword).await?;
-    Ok(words)
+    book: Option<Source>,
+    sort: Option<WordSort>,
+    max_rank: Option<i32>,
+    page: Option<u64>,
+    limit: Option<u64>,
+) -> Result<SearchResult, ApiError> {
+    if sort == Some(WordSort::Frequency) || max_rank.is_some() {
+        require_frequency_list(&registry)?;
+    }
+    let result = master_word_service::search_words(
+        &db,
+        keyword,
+        book,
+        sort,
+        max_rank,
+        page.unwrap_or(0),
+        limit.unwrap_or(50),
+    )
+    .await?;
+    Ok(result)
+}
+
+//查单词，变形会还原成原形，找不到返回 null
+#[tauri::command]
+pub async fn lookup_word(
+    db: State<'_, DatabaseConnection>,
+    text: String,
+) -> Result<Option<WordLookup>, ApiError> {
+    let word = master_word_service::lookup_word(&db, text).await?;
+    Ok(word)
 }
 
 //添加单词到学习库
 #[tauri::command]
 pub async fn add_word_to_learning(
     db: State<'_, DatabaseConnection>,
+    clock: State<'_, AppClock>,
     master_id: i32,
 ) -> Result<i32, ApiError> {
-    let new_record = user_word_service::add_word_to_learning(&db, master_id).await?;
+    reject_preview(&clock)?;
+    let new_record = user_word_service::add_word_to_learning(&db, clock.inner(), master_id).await?;
     Ok(new_record.id)
 }
 
@@ -68,19 +161,37 @@ pub async fn add_word_to_learning(
 #[tauri::command]
 pub async fn generate_new_words(
     db: State<'_, DatabaseConnection>,
+    registry: State<'_, BookRegistry>,
+    clock: State<'_, AppClock>,
     limit: u64,
+    books: Option<Vec<Source>>,
+    order: Option<NewWordOrder>,
 ) -> Result<u64, ApiError> {
-    let count = user_word_service::generate_daily_new_words(&db, limit).await?;
+    reject_preview(&clock)?;
+    if order == Some(NewWordOrder::Frequency) {
+        require_frequency_list(&registry)?;
+    }
+    let count =
+        user_word_service::generate_daily_new_words(&db, clock.inner(), limit, books, order)
+            .await?;
     Ok(count)
 }
 
 //获取要复习的单词
 #[tauri::command]
-pub async fn get_due_words(db: State<'_, DatabaseConnection>) -> Result<Vec<ReviewCard>, ApiError> {
-    let raw_resluts = user_word_service::get_due_words(&db).await?;
+pub async fn get_due_words(
+    db: State<'_, DatabaseConnection>,
+    clock: State<'_, AppClock>,
+) -> Result<Vec<ReviewCard>, ApiError> {
+    let raw_resluts = user_word_service::get_due_words(&db, clock.inner()).await?;
+    let master_ids: Vec<i32> = raw_resluts
+        .iter()
+        .map(|(user, _)| user.master_word_id)
+        .collect();
+    let mut details = master_word_service::load_word_details(&db, &master_ids).await?;
     let review_cards: Vec<ReviewCard> = raw_resluts
         .into_iter()
-        .filter_map(|(user, master)| ReviewCard::from_query_result(user, master))
+        .filter_map(|(user, master)| ReviewCard::from_query_result(user, master, &mut details))
         .collect();
     Ok(review_cards)
 }
@@ -89,18 +200,352 @@ pub async fn get_due_words(db: State<'_, DatabaseConnection>) -> Result<Vec<Revi
 #[tauri::command]
 pub async fn submit_review(
     db: State<'_, DatabaseConnection>,
+    clock: State<'_, AppClock>,
     rating_val: i32,
     user_word_id: i32,
+    duration_ms: Option<i64>,
 ) -> Result<(), ApiError> {
-    let raw_resluts = user_word_service::submit_review(&db, user_word_id, rating_val).await?;
-    Ok(raw_resluts)
+    reject_preview(&clock)?;
+    let rating = Rating::from_i32(rating_val)
+        .ok_or_else(|| ApiError::InvalidInput(format!("无效的评分: {}", rating_val)))?;
+    user_word_service::submit_review(&db, clock.inner(), user_word_id, rating, duration_ms).await?;
+    Ok(())
+}
+
+//撤销最近一次复习，返回被还原的单词，方便放回复习队列
+#[tauri::command]
+pub async fn undo_last_review(
+    db: State<'_, DatabaseConnection>,
+    clock: State<'_, AppClock>,
+) -> Result<Option<ReviewCard>, ApiError> {
+    reject_preview(&clock)?;
+    let Some((user, master)) = user_word_service::undo_last_review(&db, clock.inner()).await?
+    else {
+        return Ok(None);
+    };
+    let mut details = master_word_service::load_word_details(&db, &[user.master_word_id]).await?;
+    Ok(ReviewCard::from_query_result(user, master, &mut details))
+}
+
+//暂停 / 搁置 / 恢复单词
+#[tauri::command]
+pub async fn set_card_state(
+    db: State<'_, DatabaseConnection>,
+    user_word_id: i32,
+    state: CardState,
+) -> Result<(), ApiError> {
+    user_word_service::set_card_state(&db, user_word_id, state).await?;
+    Ok(())
+}
+
+//获取单词的复习记录
+#[tauri::command]
+pub async fn get_review_history(
+    db: State<'_, DatabaseConnection>,
+    user_word_id: i32,
+) -> Result<Vec<review_log::Model>, ApiError> {
+    let logs = review_log_service::get_review_history(&db, user_word_id).await?;
+    Ok(logs)
 }
 
 //获取单词状态
 #[tauri::command]
 pub async fn get_dashboard_stats(
     db: State<'_, DatabaseConnection>,
+    clock: State<'_, AppClock>,
 ) -> Result<DashboardStats, ApiError> {
-    let raw_resluts = stats_service::get_stats(&db).await?;
+    let raw_resluts = stats_service::get_stats(&db, clock.inner()).await?;
     Ok(raw_resluts)
 }
+
+//用复习记录拟合个人参数，返回拟合前后的损失，不直接保存
+#[tauri::command]
+pub async fn optimize_fsrs_params(
+    db: State<'_, DatabaseConnection>,
+    deck: Option<String>,
+) -> Result<OptimizeReport, ApiError> {
+    let report = fsrs_params_service::optimize(&db, deck).await?;
+    Ok(report)
+}
+
+//保存拟合出的参数
+#[tauri::command]
+pub async fn apply_fsrs_params(
+    db: State<'_, DatabaseConnection>,
+    deck: Option<String>,
+    params: Vec<f32>,
+) -> Result<(), ApiError> {
+    fsrs_params_service::apply_params(&db, deck, params).await?;
+    Ok(())
+}
+
+//读取后端设置
+#[tauri::command]
+pub async fn get_settings(db: State<'_, DatabaseConnection>) -> Result<Settings, ApiError> {
+    let settings = settings_service::get_settings(&db).await?;
+    Ok(settings)
+}
+
+//保存后端设置
+#[tauri::command]
+pub async fn update_settings(
+    db: State<'_, DatabaseConnection>,
+    registry: State<'_, BookRegistry>,
+    settings: Settings,
+) -> Result<(), ApiError> {
+    if settings.new_word_order == NewWordOrder::Frequency {
+        require_frequency_list(&registry)?;
+    }
+    settings_service::update_settings(&db, settings).await?;
+    Ok(())
+}
+
+//模拟不同目标保持率下的每日复习量
+#[tauri::command]
+pub async fn simulate_workload(
+    db: State<'_, DatabaseConnection>,
+    clock: State<'_, AppClock>,
+    retentions: Vec<f32>,
+    new_per_day: u32,
+    days: u32,
+) -> Result<Vec<WorkloadForecast>, ApiError> {
+    if days > stats_service::MAX_SIMULATION_DAYS {
+        return Err(ApiError::InvalidInput(format!(
+            "最多模拟 {} 天",
+            stats_service::MAX_SIMULATION_DAYS
+        )));
+    }
+    if new_per_day > stats_service::MAX_SIMULATION_NEW_PER_DAY {
+        return Err(ApiError::InvalidInput(format!(
+            "每天新词最多 {} 个",
+            stats_service::MAX_SIMULATION_NEW_PER_DAY
+        )));
+    }
+    if retentions.len() > stats_service::MAX_SIMULATION_RETENTIONS {
+        return Err(ApiError::InvalidInput(format!(
+            "一次最多比较 {} 个保持率",
+            stats_service::MAX_SIMULATION_RETENTIONS
+        )));
+    }
+    if !retentions.iter().all(|r| (0.7..=0.99).contains(r)) {
+        return Err(ApiError::InvalidInput(
+            "目标保持率必须在 0.70 ~ 0.99 之间".to_owned(),
+        ));
+    }
+    let forecasts =
+        stats_service::simulate_workload(&db, clock.inner(), retentions, new_per_day, days).await?;
+    Ok(forecasts)
+}
+
+//预览某一天的复习情况，date 为空则回到真实时间
+//预览期间不能复习、加词或撤销，避免把预览时间写进数据库
+#[tauri::command]
+pub fn set_preview_date(
+    clock: State<'_, AppClock>,
+    date: Option<String>,
+) -> Result<String, ApiError> {
+    let preview = date
+        .map(|d| {
+            DateTime::parse_from_rfc3339(&d)
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|e| ApiError::InvalidInput(e.to_string()))
+        })
+        .transpose()?;
+    clock.set_preview(preview);
+    Ok(clock.now().to_rfc3339())
+}
+
+//导入用户自己的词表 (CSV / TSV / JSON)，format 为空时按扩展名判断，.txt 之类的看内容
+#[tauri::command]
+pub async fn import_word_list(
+    app: AppHandle,
+    db: State<'_, DatabaseConnection>,
+    registry: State<'_, BookRegistry>,
+    path: String,
+    format: Option<ImportFormat>,
+    source: Option<Source>,
+) -> Result<ImportReport, ApiError> {
+    let path = PathBuf::from(path);
+    let content = app
+        .fs()
+        .read_to_string(path.clone())
+        .map_err(|e| ApiError::InvalidInput(e.to_string()))?;
+    let format = format
+        .or_else(|| ImportFormat::from_path(&path))
+        .or_else(|| ImportFormat::from_content(&content))
+        .ok_or_else(|| ApiError::InvalidInput("无法识别的文件格式".to_owned()))?;
+
+    let report =
+        import_service::import_word_list(&db, &content, format, source.unwrap_or(Source::Custom))
+            .await?;
+    master_word_service::fill_frequency_ranks(&db, &registry).await?;
+    dictionary_service::fill_irregular_inflections(db.inner()).await?;
+    Ok(report)
+}
+
+//导入 Anki 牌组 (.apkg)，音频复制到应用数据目录的 audio 下
+#[tauri::command]
+pub async fn import_anki_deck(
+    app: AppHandle,
+    db: State<'_, DatabaseConnection>,
+    clock: State<'_, AppClock>,
+    registry: State<'_, BookRegistry>,
+    path: String,
+    options: Option<AnkiImportOptions>,
+) -> Result<AnkiImportReport, ApiError> {
+    reject_preview(&clock)?;
+    let data = app
+        .fs()
+        .read(PathBuf::from(path))
+        .map_err(|e| ApiError::InvalidInput(e.to_string()))?;
+    // 安装后的资源目录是只读的，音频放到应用数据目录
+    let audio_dir = app
+        .path()
+        .resolve("audio", BaseDirectory::AppData)
+        .map_err(|e| ApiError::InvalidInput(e.to_string()))?;
+
+    let report = anki_import_service::import_apkg(
+        &db,
+        clock.inner(),
+        data,
+        &audio_dir,
+        options.unwrap_or_default(),
+    )
+    .await?;
+    master_word_service::fill_frequency_ranks(&db, &registry).await?;
+    dictionary_service::fill_irregular_inflections(db.inner()).await?;
+    Ok(report)
+}
+
+//用用户自己下载的词典文件 (ECDICT 格式的 CSV) 补全音标、释义和单词变形，安装包里不带词典
+//在后台跑，立即返回任务编号；进度通过 dictionary-progress 事件、结果通过 dictionary-finished 事件发给前端
+#[tauri::command]
+pub async fn enrich_from_dictionary_csv(
+    app: AppHandle,
+    db: State<'_, DatabaseConnection>,
+    path: String,
+) -> Result<u32, ApiError> {
+    let mut options = OpenOptions::new();
+    options.read(true);
+    let file = app
+        .fs()
+        .open(PathBuf::from(path), options)
+        .map_err(|e| ApiError::InvalidInput(e.to_string()))?;
+    let total_bytes = file.metadata().map(|m| m.len()).unwrap_or_default();
+
+    let job_id = dictionary_service::next_job_id();
+    let db = db.inner().clone();
+    tauri::async_runtime::spawn(async move {
+        let result = dictionary_service::enrich_from_csv(
+            &db,
+            job_id,
+            BufReader::new(file),
+            total_bytes,
+            |progress| {
+                let _ = app.emit("dictionary-progress", progress);
+            },
+        )
+        .await;
+        let _ = app.emit(
+            "dictionary-finished",
+            DictionaryFinished::new(job_id, result),
+        );
+    });
+    Ok(job_id)
+}
+
+// 通过 fs 插件写文件，手机上 path 可能是 content:// 地址
+fn write_file(app: &AppHandle, path: String, content: &[u8]) -> Result<(), ApiError> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    app.fs()
+        .open(PathBuf::from(path), options)
+        .and_then(|mut file| file.write_all(content))
+        .map_err(|e| ApiError::InvalidInput(e.to_string()))
+}
+
+//导出学习进度和复习记录到 JSON 文件，返回导出的单词数
+#[tauri::command]
+pub async fn export_progress(
+    app: AppHandle,
+    db: State<'_, DatabaseConnection>,
+    clock: State<'_, AppClock>,
+    path: String,
+) -> Result<usize, ApiError> {
+    let progress = progress_service::export_progress(&db, clock.inner()).await?;
+    let json =
+        serde_json::to_vec_pretty(&progress).map_err(|e| ApiError::InvalidInput(e.to_string()))?;
+
+    write_file(&app, path, &json)?;
+    Ok(progress.words.len())
+}
+
+//从 export_progress 导出的文件合并学习进度
+#[tauri::command]
+pub async fn import_progress(
+    app: AppHandle,
+    db: State<'_, DatabaseConnection>,
+    path: String,
+) -> Result<ProgressImportReport, ApiError> {
+    let content = app
+        .fs()
+        .read_to_string(PathBuf::from(path))
+        .map_err(|e| ApiError::InvalidInput(e.to_string()))?;
+    let file = serde_json::from_str(&content)
+        .map_err(|e| ApiError::InvalidInput(format!("进度文件格式错误: {}", e)))?;
+
+    let report = progress_service::import_progress(&db, file).await?;
+    Ok(report)
+}
+
+//按条件导出单词 (CSV 或 Anki 文本)，返回导出的单词数
+#[tauri::command]
+pub async fn export_words(
+    app: AppHandle,
+    db: State<'_, DatabaseConnection>,
+    path: String,
+    format: ExportFormat,
+    filter: Option<ExportFilter>,
+) -> Result<usize, ApiError> {
+    let (content, count) =
+        export_service::export_words(&db, &filter.unwrap_or_default(), format).await?;
+    write_file(&app, path, content.as_bytes())?;
+    Ok(count)
+}
+
+//内置词书列表 (含单词数和安装情况)
+#[tauri::command]
+pub async fn list_books(
+    db: State<'_, DatabaseConnection>,
+    registry: State<'_, BookRegistry>,
+) -> Result<Vec<BookInfo>, ApiError> {
+    let books = book_service::list_books(&db, &registry).await?;
+    Ok(books)
+}
+
+//安装包里有没有词频表，没有时界面上不显示按词频排序 / 筛选的选项
+#[tauri::command]
+pub fn has_frequency_list(registry: State<'_, BookRegistry>) -> bool {
+    registry.frequency_list().is_some()
+}
+
+//安装内置词书
+#[tauri::command]
+pub async fn install_book(
+    db: State<'_, DatabaseConnection>,
+    registry: State<'_, BookRegistry>,
+    source: Source,
+) -> Result<ImportReport, ApiError> {
+    let report = book_service::install_book(&db, &registry, source).await?;
+    Ok(report)
+}
+
+//卸载内置词书，返回删除的单词数 (正在学的单词不会删除)
+#[tauri::command]
+pub async fn uninstall_book(
+    db: State<'_, DatabaseConnection>,
+    source: Source,
+) -> Result<u64, ApiError> {
+    let removed = book_service::uninstall_book(&db, source).await?;
+    Ok(removed)
+}
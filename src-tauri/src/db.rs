@@ -1,33 +1,61 @@
-use crate::entities::{master_word, prelude::MasterWord};
-use chrono::Utc;
+use crate::{
+    books::{self, BookRegistry},
+    entities::{
+        master_word,
+        prelude::{MasterWord, WordPhrase, WordSense},
+        source_enum::Source,
+        word_phrase, word_sense,
+    },
+    hanzi,
+    services::{book_service, dictionary_service, import_service, master_word_service},
+};
 use migration::{Migrator, MigratorTrait};
 use sea_orm::{
-    ActiveValue::Set, ConnectionTrait, Database, DatabaseBackend, DatabaseConnection, DbErr,
-    EntityTrait, PaginatorTrait, Statement,
+    sea_query::{CaseStatement, Query, SimpleExpr},
+    ActiveValue::Set,
+    ColumnTrait, ConnectionTrait, Database, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QuerySelect, TransactionTrait, Value,
 };
 use serde::Deserialize;
-use std::fs;
+use std::{collections::HashMap, fs};
 use tauri::{AppHandle, Manager};
 
 #[derive(Debug, Deserialize)]
-struct JsonTranslation {
-    translation: String,
+pub(crate) struct JsonTranslation {
+    pub translation: String,
     #[serde(rename = "type")] // "type" 是 Rust 关键字，所以要改名映射
-    word_type: Option<String>,
+    pub word_type: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
-struct JsonPhrase {
-    phrase: String,
-    translation: String,
+pub(crate) struct JsonPhrase {
+    pub phrase: String,
+    pub translation: String,
 }
 
 #[derive(Debug, Deserialize)]
-struct JsonWord {
-    word: String,
-    translations: Vec<JsonTranslation>,
+pub(crate) struct JsonWord {
+    pub word: String,
+    pub translations: Vec<JsonTranslation>,
     // phrases 可能为空或不存在，用 Option
-    phrases: Option<Vec<JsonPhrase>>,
+    pub phrases: Option<Vec<JsonPhrase>>,
+    // 导入的词表可能带音标，内置 JSON 没有
+    #[serde(default)]
+    pub pronunciation: Option<String>,
+}
+
+impl JsonWord {
+    // 拼成一行一个词性的释义，存到 master_word.definition
+    pub(crate) fn definition(&self) -> String {
+        self.translations
+            .iter()
+            .map(|t| match &t.word_type {
+                Some(wt) => format!("{}. {}", wt, t.translation),
+                None => t.translation.clone(),
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
 }
 
 pub async fn init(app_hadle: &AppHandle) -> Result<DatabaseConnection, DbErr> {
@@ -62,66 +90,268 @@ pub async fn init(app_hadle: &AppHandle) -> Result<DatabaseConnection, DbErr> {
     Ok(db)
 }
 
-//插入假数据函数
-pub async fn seed(db: &DatabaseConnection) -> Result<(), DbErr> {
+// 按词性拆开的释义和短语，对应 word_sense / word_phrase 表
+pub(crate) fn senses_and_phrases(
+    word: &JsonWord,
+    master_id: i32,
+) -> (Vec<word_sense::ActiveModel>, Vec<word_phrase::ActiveModel>) {
+    let senses = word
+        .translations
+        .iter()
+        .enumerate()
+        .map(|(i, t)| word_sense::ActiveModel {
+            master_word_id: Set(master_id),
+            pos: Set(t.word_type.clone()),
+            translation: Set(t.translation.clone()),
+            sort_order: Set(i as i32),
+            pinyin: Set(Some(hanzi::plain_pinyin(&t.translation))),
+            ..Default::default()
+        })
+        .collect();
+
+    let phrases = word
+        .phrases
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, p)| word_phrase::ActiveModel {
+            master_word_id: Set(master_id),
+            phrase: Set(p.phrase.clone()),
+            translation: Set(p.translation.clone()),
+            sort_order: Set(i as i32),
+            ..Default::default()
+        })
+        .collect();
+
+    (senses, phrases)
+}
+
+// 分批写入释义和短语，防止一次插入太多导致 SQLite 报错
+pub(crate) async fn insert_details<C: ConnectionTrait>(
+    db: &C,
+    senses: Vec<word_sense::ActiveModel>,
+    phrases: Vec<word_phrase::ActiveModel>,
+) -> Result<(), DbErr> {
+    for chunk in senses.chunks(100) {
+        WordSense::insert_many(chunk.to_vec()).exec(db).await?;
+    }
+    for chunk in phrases.chunks(100) {
+        WordPhrase::insert_many(chunk.to_vec()).exec(db).await?;
+    }
+    Ok(())
+}
+
+// 按 id 批量改一列：每 200 行一条 UPDATE ... SET column = CASE WHEN id = ? THEN ? ... END
+pub(crate) async fn update_column_by_id<C, Col, V>(
+    db: &C,
+    id: Col,
+    column: Col,
+    values: Vec<(i32, V)>,
+) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+    Col: ColumnTrait,
+    V: Into<Value> + Clone,
+{
+    for chunk in values.chunks(200) {
+        let mut case = CaseStatement::new();
+        for (row, value) in chunk {
+            case = case.case(id.eq(*row), SimpleExpr::Value(value.clone().into()));
+        }
+        let ids: Vec<i32> = chunk.iter().map(|(row, _)| *row).collect();
+        let update = Query::update()
+            .table(column.entity_name())
+            .value(column, case)
+            .and_where(id.is_in(ids))
+            .to_owned();
+        db.execute(db.get_database_backend().build(&update)).await?;
+    }
+    Ok(())
+}
+
+// 老数据库只有拼接好的 definition，这里按单词原文从 JSON 补上释义和短语
+async fn backfill_details(db: &DatabaseConnection, registry: &BookRegistry) -> Result<(), DbErr> {
+    if WordSense::find().count(db).await? > 0 || !has_high_school(registry) {
+        return Ok(());
+    }
+    println!("Backfilling word senses and phrases from JSON...");
+
+    let ids: HashMap<String, i32> = MasterWord::find()
+        .select_only()
+        .column(master_word::Column::Text)
+        .column(master_word::Column::Id)
+        .into_tuple::<(String, i32)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut senses = Vec::new();
+    let mut phrases = Vec::new();
+    for word in registry.load(&Source::HighSchool)?.iter() {
+        if let Some(&id) = ids.get(&word.word) {
+            let (s, p) = senses_and_phrases(word, id);
+            senses.extend(s);
+            phrases.extend(p);
+        }
+    }
+
+    let txn = db.begin().await?;
+    insert_details(&txn, senses, phrases).await?;
+    txn.commit().await?;
+
+    Ok(())
+}
+
+// 老数据库的释义没有拼音，启动时补上
+async fn backfill_pinyin(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let missing: Vec<(i32, String)> = WordSense::find()
+        .filter(word_sense::Column::Pinyin.is_null())
+        .select_only()
+        .column(word_sense::Column::Id)
+        .column(word_sense::Column::Translation)
+        .into_tuple()
+        .all(db)
+        .await?;
+    if missing.is_empty() {
+        return Ok(());
+    }
+    println!("Backfilling pinyin for {} senses...", missing.len());
+
+    let pinyin: Vec<(i32, String)> = missing
+        .into_iter()
+        .map(|(id, translation)| (id, hanzi::plain_pinyin(&translation)))
+        .collect();
+    let txn = db.begin().await?;
+    update_column_by_id(
+        &txn,
+        word_sense::Column::Id,
+        word_sense::Column::Pinyin,
+        pinyin,
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(())
+}
+
+// 内置的高中词书在不在安装包里
+fn has_high_school(registry: &BookRegistry) -> bool {
+    books::find_book(&Source::HighSchool).is_some_and(|b| registry.is_available(b))
+}
+
+//插入假数据函数：空数据库先装上内置的高中词书
+pub async fn seed(db: &DatabaseConnection, registry: &BookRegistry) -> Result<(), DbErr> {
     let count = MasterWord::find().count(db).await?;
     if count > 0 {
         println!("Database already has data ({} rows), skipping seed.", count);
+        backfill_details(db, registry).await?;
+        backfill_pinyin(db).await?;
+        master_word_service::refresh_frequency_ranks(db, registry).await?;
+        dictionary_service::fill_irregular_inflections(db).await?;
+        return Ok(());
+    }
+    if !has_high_school(registry) {
+        eprintln!("⚠️ 安装包里没有高中词书，词库先留空");
         return Ok(());
     }
     println!("Database is empty. Seeding from JSON...");
 
-    // B. 【核心】在编译时读取 JSON 文件
-    // 注意路径：相对于当前 .rs 文件的位置，或者项目根目录
-    // 假设 db.rs 在 src-tauri/src/，json 在 src-tauri/assets/
-    let json_data = include_str!("../assets/high_school_words.json");
-    let words: Vec<JsonWord> = serde_json::from_str(json_data).expect("Failed to parse words.json");
+    let words = registry.load(&Source::HighSchool)?;
     println!(
         "Found {} words in JSON. Preparing to insert...",
         words.len()
     );
+    import_service::insert_words(db, &words, &Source::HighSchool).await?;
+    book_service::set_word_count(db, &Source::HighSchool, words.len()).await?;
+    master_word_service::refresh_frequency_ranks(db, registry).await?;
+    dictionary_service::fill_irregular_inflections(db).await?;
 
-    // D. 数据转换：JsonWord -> master_word::ActiveModel
-    // 为了防止一次插入太多导致 SQLite 报错，我们可以分批插入，比如每批 100 个
-    let batch_size = 100;
-    let mut batch: Vec<master_word::ActiveModel> = Vec::new();
+    println!("✅ Data seeded successfully!");
 
-    for word in words {
-        let definition_str = word
-            .translations
-            .iter()
-            .map(|t| {
-                match &t.word_type {
-                    Some(wt) => format!("{}. {}", wt, t.translation),
-                    None => format!("{}", t.translation), // 或者 format!("未知. {}", t.translation)
-                }
-            })
-            .collect::<Vec<String>>()
-            .join("/n");
-
-        let active_model = master_word::ActiveModel {
-            text: Set(word.word),
-            definition: Set(definition_str),
-            source: Set(Some("高中".to_owned())), // 标记来源
-            audio_url: Set(None),                 // 暂时没有音频
-            pronunciation: Set(None),             // JSON 里没音标，暂时留空
-            created_at: Set(Utc::now().into()),
-            ..Default::default() // ID 自增
-        };
-
-        batch.push(active_model);
-
-        if batch.len() >= batch_size {
-            MasterWord::insert_many(batch.clone()).exec(db).await?;
-            batch.clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::QueryOrder;
+    use std::{fs, path::PathBuf};
+
+    // 临时的资源目录，放一本两个词的高中词书，词频表可选
+    fn resource_dir(name: &str, frequency: Option<&str>) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "yaya-{}-{}",
+            name,
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        fs::create_dir_all(dir.join("resources/books")).unwrap();
+        fs::write(
+            dir.join("resources/books/high_school_words.json"),
+            r#"[{"word":"apple","translations":[{"translation":"苹果","type":"n"}]},
+                {"word":"Abandon","translations":[{"translation":"放弃","type":"v"}]}]"#,
+        )
+        .unwrap();
+        if let Some(list) = frequency {
+            fs::create_dir_all(dir.join("resources/frequency")).unwrap();
+            fs::write(dir.join("resources/frequency/word_frequency.txt"), list).unwrap();
         }
+        dir
     }
 
-    if !batch.is_empty() {
-        MasterWord::insert_many(batch).exec(db).await?;
+    async fn ranks(db: &DatabaseConnection) -> Vec<(String, Option<i32>)> {
+        MasterWord::find()
+            .select_only()
+            .column(master_word::Column::Text)
+            .column(master_word::Column::FrequencyRank)
+            .order_by_asc(master_word::Column::Text)
+            .into_tuple()
+            .all(db)
+            .await
+            .unwrap()
     }
 
-    println!("✅ Data seeded successfully!");
+    #[test]
+    fn seed_fills_frequency_ranks() {
+        tauri::async_runtime::block_on(async {
+            let db = Database::connect("sqlite::memory:").await.unwrap();
+            Migrator::up(&db, None).await.unwrap();
+            let dir = resource_dir("seed", Some("# 注释\nthe 100\napple 50\nabandon,3\n"));
 
-    Ok(())
+            seed(&db, &BookRegistry::new(dir.clone())).await.unwrap();
+            assert_eq!(
+                ranks(&db).await,
+                vec![
+                    ("Abandon".to_owned(), Some(3)),
+                    ("apple".to_owned(), Some(2))
+                ]
+            );
+
+            // 词频表换了，下次启动重新算
+            fs::write(
+                dir.join("resources/frequency/word_frequency.txt"),
+                "abandon\n",
+            )
+            .unwrap();
+            seed(&db, &BookRegistry::new(dir.clone())).await.unwrap();
+            assert_eq!(
+                ranks(&db).await,
+                vec![("Abandon".to_owned(), Some(1)), ("apple".to_owned(), None)]
+            );
+            let _ = fs::remove_dir_all(dir);
+        });
+    }
+
+    #[test]
+    fn seed_without_frequency_list_leaves_ranks_empty() {
+        tauri::async_runtime::block_on(async {
+            let db = Database::connect("sqlite::memory:").await.unwrap();
+            Migrator::up(&db, None).await.unwrap();
+            let dir = resource_dir("seed-nofreq", None);
+
+            seed(&db, &BookRegistry::new(dir.clone())).await.unwrap();
+            assert!(ranks(&db).await.iter().all(|(_, rank)| rank.is_none()));
+            let _ = fs::remove_dir_all(dir);
+        });
+    }
 }
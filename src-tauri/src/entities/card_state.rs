@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+// user_word.status 的取值，数据库里存 i16
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CardState {
+    New = 0,        // 新词，还没学过
+    Learning = 1,   // 学习中，按学习步骤短间隔重复
+    Review = 2,     // 已毕业，按 FSRS 间隔复习
+    Relearning = 3, // 复习时忘了，按重学步骤重新学习
+    Suspended = 4,  // 手动暂停，不再出现
+    Buried = 5,     // 暂时搁置，不再出现直到恢复
+}
+
+impl CardState {
+    pub fn from_i16(v: i16) -> Option<Self> {
+        match v {
+            0 => Some(Self::New),
+            1 => Some(Self::Learning),
+            2 => Some(Self::Review),
+            3 => Some(Self::Relearning),
+            4 => Some(Self::Suspended),
+            5 => Some(Self::Buried),
+            _ => None,
+        }
+    }
+
+    pub fn as_i16(self) -> i16 {
+        self as i16
+    }
+
+    // 暂停和搁置的单词不参与复习
+    pub fn is_active(self) -> bool {
+        !matches!(self, Self::Suspended | Self::Buried)
+    }
+
+    // 允许的状态转换
+    // 评分: New -> Learning/Review, Learning -> Learning/Review,
+    //       Review -> Review/Relearning, Relearning -> Relearning/Review
+    // 手动: 活跃状态 -> Suspended/Buried, Suspended/Buried -> 暂停前的状态
+    pub fn can_transition_to(self, next: Self) -> bool {
+        use CardState::*;
+        matches!(
+            (self, next),
+            (New, Learning | Review)
+                | (Learning, Learning | Review)
+                | (Review, Review | Relearning)
+                | (Relearning, Relearning | Review)
+                | (New | Learning | Review | Relearning, Suspended | Buried)
+                | (Suspended | Buried, New | Learning | Review | Relearning)
+                | (Suspended, Buried)
+                | (Buried, Suspended)
+        )
+    }
+}
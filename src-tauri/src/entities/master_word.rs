@@ -12,14 +12,24 @@ pub struct Model {
     pub definition: String,
     pub pronunciation: Option<String>,
     pub audio_url: Option<String>,
-    pub source: Option<String>,
     pub created_at: DateTimeWithTimeZone,
+    pub frequency_rank: Option<i32>,
+    pub pronunciation_us: Option<String>,
+    pub pronunciation_uk: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::user_word::Entity")]
     UserWord,
+    #[sea_orm(has_many = "super::word_book_membership::Entity")]
+    WordBookMembership,
+    #[sea_orm(has_many = "super::word_inflection::Entity")]
+    WordInflection,
+    #[sea_orm(has_many = "super::word_phrase::Entity")]
+    WordPhrase,
+    #[sea_orm(has_many = "super::word_sense::Entity")]
+    WordSense,
 }
 
 impl Related<super::user_word::Entity> for Entity {
@@ -28,4 +38,41 @@ impl Related<super::user_word::Entity> for Entity {
     }
 }
 
+impl Related<super::word_book_membership::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WordBookMembership.def()
+    }
+}
+
+impl Related<super::word_book::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::word_book_membership::Relation::WordBook.def()
+    }
+    fn via() -> Option<RelationDef> {
+        Some(
+            super::word_book_membership::Relation::MasterWord
+                .def()
+                .rev(),
+        )
+    }
+}
+
+impl Related<super::word_inflection::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WordInflection.def()
+    }
+}
+
+impl Related<super::word_phrase::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WordPhrase.def()
+    }
+}
+
+impl Related<super::word_sense::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WordSense.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
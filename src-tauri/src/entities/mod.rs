@@ -1,4 +1,13 @@
+pub mod app_setting;
+pub mod card_state;
+pub mod fsrs_params;
 pub mod master_word;
 pub mod prelude;
+pub mod review_log;
 pub mod source_enum;
 pub mod user_word;
+pub mod word_book;
+pub mod word_book_membership;
+pub mod word_inflection;
+pub mod word_phrase;
+pub mod word_sense;
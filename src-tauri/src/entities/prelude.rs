@@ -1,4 +1,12 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.14
 
+pub use super::app_setting::Entity as AppSetting;
+pub use super::fsrs_params::Entity as FsrsParams;
 pub use super::master_word::Entity as MasterWord;
+pub use super::review_log::Entity as ReviewLog;
 pub use super::user_word::Entity as UserWord;
+pub use super::word_book::Entity as WordBook;
+pub use super::word_book_membership::Entity as WordBookMembership;
+pub use super::word_inflection::Entity as WordInflection;
+pub use super::word_phrase::Entity as WordPhrase;
+pub use super::word_sense::Entity as WordSense;
@@ -4,7 +4,8 @@ use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
 // 移除了所有 sea-orm 的 derive 宏，只保留基础功能
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+// 每个来源对应 word_book 表里的一本词书，code 就是 as_ref() 的字符串
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Source {
     ElementarySchool,
     JuniorHighSchool,
@@ -16,6 +16,8 @@ pub struct Model {
     pub last_review: Option<DateTimeWithTimeZone>,
     pub status: i16,
     pub added_at: DateTimeWithTimeZone,
+    pub learning_step: i16,
+    pub resume_status: Option<i16>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -28,6 +30,8 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     MasterWord,
+    #[sea_orm(has_many = "super::review_log::Entity")]
+    ReviewLog,
 }
 
 impl Related<super::master_word::Entity> for Entity {
@@ -36,4 +40,10 @@ impl Related<super::master_word::Entity> for Entity {
     }
 }
 
+impl Related<super::review_log::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReviewLog.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -5,6 +5,7 @@ use serde::Serialize;
 #[derive(Debug, Serialize)]
 pub enum ApiError {
     DatabaseError(String),
+    InvalidInput(String),
 }
 
 // 3. (推荐) 为 ApiError 实现 From<sea_orm::DbErr>
@@ -0,0 +1,197 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use crate::learning_day::LearningDay;
+
+// 固定种子的伪随机数 (SplitMix64)，保证同样的输入得到同样的结果
+pub struct SplitMix64(pub u64);
+
+impl SplitMix64 {
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // [0, 1) 之间的随机数
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+// 种子由单词 id 和复习当天决定：同一个单词同一天算出来的结果总是一样
+pub fn fuzz_seed(user_word_id: i32, now: DateTime<Utc>, learning_day: &LearningDay) -> u64 {
+    let day = learning_day.start_of(learning_day.day_of(now)).timestamp() as u64;
+    SplitMix64(((user_word_id as u64) << 32) ^ day).next_u64()
+}
+
+// 间隔的可浮动范围 (天)，间隔越长浮动比例越小
+// < 2.5 天不浮动，2.5~7 天 ±15%，7~20 天 ±10%，20 天以上 ±5%
+pub fn fuzz_range(interval_days: f32) -> (i64, i64) {
+    if interval_days < 2.5 {
+        let d = interval_days.round().max(1.0) as i64;
+        return (d, d);
+    }
+
+    let mut delta = 1.0;
+    for (start, end, factor) in [(2.5, 7.0, 0.15), (7.0, 20.0, 0.1), (20.0, f32::MAX, 0.05)] {
+        delta += factor * (interval_days.min(end) - start).max(0.0);
+    }
+
+    let min = (interval_days - delta).round().max(2.0) as i64;
+    let max = (interval_days + delta).round() as i64;
+    (min, max.max(min))
+}
+
+// 在浮动范围内随机取一个间隔
+pub fn fuzzed_interval(interval_days: f32, seed: u64) -> i64 {
+    let (min, max) = fuzz_range(interval_days);
+    let span = (max - min + 1) as u64;
+    min + (SplitMix64(seed).next_u64() % span) as i64
+}
+
+// 负载均衡：在浮动范围内挑已到期单词最少的一天，一样少时用种子随机挑
+// due_counts: 从今天算起第几天 -> 那天到期的单词数
+pub fn load_balanced_interval(
+    interval_days: f32,
+    seed: u64,
+    due_counts: &HashMap<i64, u64>,
+) -> i64 {
+    let (min, max) = fuzz_range(interval_days);
+    let lightest = (min..=max)
+        .map(|d| due_counts.get(&d).copied().unwrap_or(0))
+        .min()
+        .unwrap_or(0);
+    let candidates: Vec<i64> = (min..=max)
+        .filter(|d| due_counts.get(d).copied().unwrap_or(0) == lightest)
+        .collect();
+
+    let index = (SplitMix64(seed).next_u64() % candidates.len() as u64) as usize;
+    candidates[index]
+}
+
+// 同一批加入的新词错开第一次到期的时间：在 window 秒内均匀排开，第一个是 0
+// 返回每个单词相对 now 的秒数，顺序和传入的单词一致
+pub fn stagger_offsets(count: usize, window_secs: i64) -> Vec<i64> {
+    let step = if count > 1 {
+        window_secs.max(0) / count as i64
+    } else {
+        0
+    };
+    (0..count as i64).map(|i| i * step).collect()
+}
+
+// 把到期时间按 "比 now 晚几个学习日" 分组计数
+pub fn due_day_counts(
+    dues: &[DateTime<Utc>],
+    now: DateTime<Utc>,
+    learning_day: &LearningDay,
+) -> HashMap<i64, u64> {
+    let mut counts = HashMap::new();
+    for due in dues {
+        let day = learning_day.days_between(now, *due);
+        *counts.entry(day).or_insert(0) += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, FixedClock};
+    use chrono::{Duration, TimeZone};
+
+    fn clock() -> FixedClock {
+        FixedClock(Utc.with_ymd_and_hms(2025, 3, 1, 10, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn short_intervals_are_not_fuzzed() {
+        assert_eq!(fuzz_range(1.0), (1, 1));
+        assert_eq!(fuzz_range(2.4), (2, 2));
+        assert_eq!(fuzzed_interval(2.0, 42), 2);
+    }
+
+    #[test]
+    fn fuzz_range_grows_with_interval() {
+        let (min, max) = fuzz_range(10.0);
+        assert!(min < 10 && max > 10);
+        let (long_min, long_max) = fuzz_range(100.0);
+        assert!(long_max - long_min > max - min);
+        // 长间隔的浮动比例更小
+        assert!(((long_max - long_min) as f32 / 100.0) < ((max - min) as f32 / 10.0));
+    }
+
+    #[test]
+    fn fuzzed_interval_is_deterministic_and_in_range() {
+        let ld = LearningDay::new("Asia/Shanghai", 4);
+        let now = clock().now();
+        let (min, max) = fuzz_range(30.0);
+        let intervals: Vec<i64> = (1..=50)
+            .map(|id| fuzzed_interval(30.0, fuzz_seed(id, now, &ld)))
+            .collect();
+        assert!(intervals.iter().all(|d| (min..=max).contains(d)));
+        // 不同单词的间隔要错开，不能全部一样
+        assert!(intervals.iter().any(|d| *d != intervals[0]));
+
+        let again: Vec<i64> = (1..=50)
+            .map(|id| fuzzed_interval(30.0, fuzz_seed(id, now, &ld)))
+            .collect();
+        assert_eq!(intervals, again);
+    }
+
+    #[test]
+    fn seed_is_stable_within_a_learning_day() {
+        let ld = LearningDay::new("Asia/Shanghai", 4);
+        let now = clock().now();
+        assert_eq!(
+            fuzz_seed(7, now, &ld),
+            fuzz_seed(7, now + Duration::hours(3), &ld)
+        );
+        assert_ne!(
+            fuzz_seed(7, now, &ld),
+            fuzz_seed(7, now + Duration::days(1), &ld)
+        );
+    }
+
+    #[test]
+    fn load_balancing_picks_the_lightest_day() {
+        let (min, max) = fuzz_range(10.0);
+        let mut counts: HashMap<i64, u64> = (min..=max).map(|d| (d, 20)).collect();
+        counts.insert(max, 3);
+        for seed in 0..20 {
+            assert_eq!(load_balanced_interval(10.0, seed, &counts), max);
+        }
+
+        // 一样少时在范围内随机挑
+        let picks: Vec<i64> = (0..20)
+            .map(|seed| load_balanced_interval(10.0, seed, &HashMap::new()))
+            .collect();
+        assert!(picks.iter().all(|d| (min..=max).contains(d)));
+    }
+
+    #[test]
+    fn due_counts_follow_learning_days() {
+        let ld = LearningDay::new("Asia/Shanghai", 4);
+        let now = clock().now();
+        let dues = [
+            now + Duration::hours(1),
+            now + Duration::days(1),
+            now + Duration::days(1) + Duration::hours(1),
+            now + Duration::days(3),
+        ];
+        let counts = due_day_counts(&dues, now, &ld);
+        assert_eq!(counts.get(&0), Some(&1));
+        assert_eq!(counts.get(&1), Some(&2));
+        assert_eq!(counts.get(&3), Some(&1));
+    }
+
+    #[test]
+    fn new_cards_are_staggered() {
+        assert_eq!(stagger_offsets(1, 3600), vec![0]);
+        assert_eq!(stagger_offsets(4, 3600), vec![0, 900, 1800, 2700]);
+        assert_eq!(stagger_offsets(3, -5), vec![0, 0, 0]);
+    }
+}
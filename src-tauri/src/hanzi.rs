@@ -0,0 +1,110 @@
+use pinyin::ToPinyin;
+use std::{collections::HashSet, sync::OnceLock};
+
+// 一个搜索词最多试几种音节切法，避免很长的输入切出太多种
+const MAX_SEGMENTATIONS: usize = 8;
+
+// 一个汉字不带声调的拼音，ü 按输入法的习惯写成 v
+fn plain(p: pinyin::Pinyin) -> String {
+    p.plain().replace('ü', "v")
+}
+
+// 汉字转成不带声调的拼音，音节之间用空格隔开，"苹果, 苹果树" -> "ping guo , ping guo shu"
+// 多音字取最常用的读音，非汉字原样保留
+pub fn plain_pinyin(text: &str) -> String {
+    let mut out = String::new();
+    for (c, p) in text.chars().zip(text.to_pinyin()) {
+        match p {
+            Some(p) => {
+                out.push(' ');
+                out.push_str(&plain(p));
+                out.push(' ');
+            }
+            None => out.extend(c.to_lowercase()),
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// 所有不带声调的拼音音节，从汉字的读音里收集一次
+// 嗯 (n / ng)、呣 (m) 这类没有元音的叹词读音不算，免得 "xian" 被切成 "xia n"
+fn syllables() -> &'static HashSet<String> {
+    static SYLLABLES: OnceLock<HashSet<String>> = OnceLock::new();
+    SYLLABLES.get_or_init(|| {
+        ('\u{3400}'..='\u{9fff}')
+            .filter_map(|c| c.to_pinyin())
+            .map(plain)
+            .filter(|s| s.contains(['a', 'e', 'i', 'o', 'u', 'v']))
+            .collect()
+    })
+}
+
+// 把连写的拼音切成音节，按最长的音节先试；parts 之间是用户输入的空格或隔音符号，一定是音节边界
+fn split_syllables<'a>(parts: &[&'a str], current: &mut Vec<&'a str>, found: &mut Vec<String>) {
+    if found.len() >= MAX_SEGMENTATIONS {
+        return;
+    }
+    let Some((&part, rest)) = parts.split_first() else {
+        found.push(format!(" {} ", current.join(" ")));
+        return;
+    };
+    if part.is_empty() {
+        return split_syllables(rest, current, found);
+    }
+    for end in (1..=part.len()).rev() {
+        let (head, tail) = part.split_at(end);
+        if !syllables().contains(head) {
+            continue;
+        }
+        let mut next = vec![tail];
+        next.extend_from_slice(rest);
+        current.push(head);
+        split_syllables(&next, current, found);
+        current.pop();
+    }
+}
+
+// 把搜索词当成拼音，切成音节后的匹配模式：" ping guo "，配合 ' ' || pinyin || ' ' 只在音节边界上匹配
+// 可以带空格或隔音符号 ("ping guo"、"xi'an")；一种切法都没有 (不是拼音) 时返回空
+pub fn pinyin_patterns(keyword: &str) -> Vec<String> {
+    let keyword = keyword.trim().to_lowercase();
+    let is_separator = |c: char| c.is_whitespace() || c == '\'';
+    if keyword.is_empty()
+        || !keyword
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || is_separator(c))
+    {
+        return Vec::new();
+    }
+
+    let parts: Vec<&str> = keyword.split(is_separator).collect();
+    let mut found = Vec::new();
+    split_syllables(&parts, &mut Vec::new(), &mut found);
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_han_and_keeps_the_rest() {
+        assert_eq!(plain_pinyin("苹果, 苹果树"), "ping guo , ping guo shu");
+        assert_eq!(plain_pinyin("T恤"), "t xu");
+        assert_eq!(plain_pinyin("绿色"), "lv se");
+    }
+
+    #[test]
+    fn pinyin_patterns_split_on_syllables() {
+        // 最长的音节先试，"guo" 也可以是 "gu o"
+        assert_eq!(pinyin_patterns("Ping Guo"), [" ping guo ", " ping gu o "]);
+        assert_eq!(pinyin_patterns("pingguo"), [" ping guo ", " ping gu o "]);
+        // 连写有歧义时每种切法都试，用户写了隔音符号就按它切
+        assert_eq!(pinyin_patterns("xian"), [" xian ", " xi an "]);
+        assert_eq!(pinyin_patterns("xi'an"), [" xi an "]);
+        assert!(pinyin_patterns("苹果").is_empty());
+        assert!(pinyin_patterns("e-mail").is_empty());
+        assert!(pinyin_patterns("apple").is_empty());
+        assert!(pinyin_patterns(" ").is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+// 用户所在的时区
+#[derive(Debug, Clone, Copy)]
+enum Zone {
+    Local,     // 跟随设备的时区设置
+    Named(Tz), // IANA 时区，比如 Asia/Shanghai
+}
+
+impl Zone {
+    fn local_of(self, t: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Zone::Local => t.with_timezone(&Local).naive_local(),
+            Zone::Named(tz) => t.with_timezone(&tz).naive_local(),
+        }
+    }
+
+    // 本地时间对应的时刻；夏令时重复的一小时取较早的那个
+    fn utc_of(self, local: &NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Zone::Local => Local
+                .from_local_datetime(local)
+                .earliest()
+                .map(|t| t.with_timezone(&Utc)),
+            Zone::Named(tz) => tz
+                .from_local_datetime(local)
+                .earliest()
+                .map(|t| t.with_timezone(&Utc)),
+        }
+    }
+}
+
+// 学习日：按用户所在时区，在每天的 rollover_hour 点切换到下一天
+// 比如北京时间、凌晨 4 点切换，那么 3:59 还算 "昨天"
+// 时区按 IANA 名字计算，夏令时地区切换时间跟着当地时间走
+#[derive(Debug, Clone, Copy)]
+pub struct LearningDay {
+    zone: Zone,
+    rollover_hour: u32,
+}
+
+impl LearningDay {
+    // timezone 为空或不认识时跟随设备时区
+    pub fn new(timezone: &str, rollover_hour: u32) -> Self {
+        let zone = timezone
+            .parse::<Tz>()
+            .map(Zone::Named)
+            .unwrap_or(Zone::Local);
+        Self {
+            zone,
+            rollover_hour: rollover_hour.min(23),
+        }
+    }
+
+    // 某个时刻属于哪一个学习日
+    pub fn day_of(&self, t: DateTime<Utc>) -> NaiveDate {
+        (self.zone.local_of(t) - Duration::hours(self.rollover_hour as i64)).date()
+    }
+
+    // 某个学习日开始的时刻
+    pub fn start_of(&self, day: NaiveDate) -> DateTime<Utc> {
+        let local = day
+            .and_hms_opt(self.rollover_hour, 0, 0)
+            .expect("rollover hour is always valid");
+        // 切换时间正好落在夏令时跳过的那一小时里，就往后推一小时
+        self.zone
+            .utc_of(&local)
+            .or_else(|| self.zone.utc_of(&(local + Duration::hours(1))))
+            .unwrap_or_else(|| Utc.from_utc_datetime(&local))
+    }
+
+    // 从 now 所在学习日算起，第 days 天结束的时刻 (0 = 今天结束)
+    pub fn end_of(&self, now: DateTime<Utc>, days: i64) -> DateTime<Utc> {
+        self.start_of(self.day_of(now) + Duration::days(days + 1))
+    }
+
+    // 两个时刻相差几个学习日
+    pub fn days_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> i64 {
+        (self.day_of(to) - self.day_of(from)).num_days()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn day_rolls_over_at_local_hour() {
+        // 北京时间，凌晨 4 点切换
+        let ld = LearningDay::new("Asia/Shanghai", 4);
+        let day = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        // 北京时间 3 月 1 日 3:59 还算 2 月 28 日
+        assert_eq!(ld.day_of(utc(2025, 2, 28, 19, 59)), day.pred_opt().unwrap());
+        assert_eq!(ld.day_of(utc(2025, 2, 28, 20, 0)), day);
+        assert_eq!(ld.start_of(day), utc(2025, 2, 28, 20, 0));
+    }
+
+    #[test]
+    fn end_of_counts_whole_learning_days() {
+        let ld = LearningDay::new("Asia/Shanghai", 4);
+        let now = utc(2025, 3, 1, 10, 0); // 北京时间 18:00
+        assert_eq!(ld.end_of(now, 0), utc(2025, 3, 1, 20, 0));
+        assert_eq!(ld.end_of(now, 1), utc(2025, 3, 2, 20, 0));
+    }
+
+    #[test]
+    fn days_between_uses_learning_days_not_hours() {
+        let ld = LearningDay::new("Asia/Shanghai", 4);
+        let now = utc(2025, 3, 1, 10, 0);
+        // 只差 11 小时，但已经跨过了切换时间
+        assert_eq!(ld.days_between(now, utc(2025, 3, 1, 21, 0)), 1);
+        assert_eq!(ld.days_between(now, utc(2025, 3, 1, 19, 0)), 0);
+        assert_eq!(ld.days_between(now, utc(2025, 2, 28, 10, 0)), -1);
+    }
+
+    #[test]
+    fn rollover_follows_daylight_saving() {
+        // 纽约 2025-03-09 开始夏令时，切换时间一直是当地 4 点
+        let ld = LearningDay::new("America/New_York", 4);
+        let before = NaiveDate::from_ymd_opt(2025, 3, 8).unwrap();
+        let after = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        assert_eq!(ld.start_of(before), utc(2025, 3, 8, 9, 0));
+        assert_eq!(ld.start_of(after), utc(2025, 3, 10, 8, 0));
+        // 跨过夏令时开始的那个学习日只有 23 小时
+        let now = utc(2025, 3, 8, 12, 0);
+        assert_eq!(
+            ld.end_of(now, 0) - ld.start_of(ld.day_of(now)),
+            Duration::hours(23)
+        );
+    }
+
+    #[test]
+    fn skipped_rollover_hour_moves_forward() {
+        // 纽约夏令时开始时 2 点直接跳到 3 点
+        let ld = LearningDay::new("America/New_York", 2);
+        let day = NaiveDate::from_ymd_opt(2025, 3, 9).unwrap();
+        assert_eq!(ld.start_of(day), utc(2025, 3, 9, 7, 0));
+    }
+
+    #[test]
+    fn invalid_hour_is_clamped() {
+        let ld = LearningDay::new("Asia/Shanghai", 30);
+        let now = utc(2025, 3, 1, 10, 0);
+        assert_eq!(ld.end_of(now, 0), utc(2025, 3, 1, 15, 0));
+    }
+}
@@ -2,12 +2,20 @@ use tauri::Manager;
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 mod algorithm;
+mod books;
+mod clock;
 mod commands;
 mod db;
 pub mod entities;
 pub mod error;
+mod fuzz;
+mod hanzi;
+mod learning_day;
 mod models;
+mod morphology;
+mod optimizer;
 mod services;
+mod simulator;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -20,17 +28,38 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init());
 
     builder
+        .manage(clock::AppClock::default())
         .setup(|app| setup_database(app))
         .invoke_handler(tauri::generate_handler![
             commands::get_all_master_words,
             commands::get_master_word_by_first_letter,
             commands::get_words_list_filiter,
             commands::search_master_words,
+            commands::lookup_word,
             commands::add_word_to_learning,
             commands::generate_new_words,
             commands::get_due_words,
             commands::submit_review,
+            commands::undo_last_review,
+            commands::set_card_state,
             commands::get_dashboard_stats,
+            commands::get_review_history,
+            commands::optimize_fsrs_params,
+            commands::apply_fsrs_params,
+            commands::get_settings,
+            commands::update_settings,
+            commands::simulate_workload,
+            commands::set_preview_date,
+            commands::import_word_list,
+            commands::import_anki_deck,
+            commands::export_progress,
+            commands::import_progress,
+            commands::export_words,
+            commands::list_books,
+            commands::has_frequency_list,
+            commands::install_book,
+            commands::uninstall_book,
+            commands::enrich_from_dictionary_csv,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -44,11 +73,18 @@ fn setup_database(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
             .await
             .expect("Database initialization failed");
 
+        // 内置词书注册表，词书内容用到时才读取
+        let resource_dir = handel.path().resource_dir().expect("找不到资源目录");
+        let registry = books::BookRegistry::new(resource_dir);
+
         //注入数据
-        db::seed(&db_conn).await.expect("Failed to seed database");
+        db::seed(&db_conn, &registry)
+            .await
+            .expect("Failed to seed database");
 
         // 将数据库连接池放入 Tauri 的状态管理器中
         handel.manage(db_conn);
+        handel.manage(registry);
     });
     Ok(())
 }
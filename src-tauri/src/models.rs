@@ -1,25 +1,13 @@
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
+use std::collections::HashMap;
 
+use crate::entities::card_state::CardState;
 use crate::entities::master_word;
+use crate::entities::source_enum::Source;
 use crate::entities::user_word;
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Word {
-    pub id: i64,
-    pub text: String,
-    pub definition: String,
-
-    // --- 间隔重复系统 (SRS) 核心字段 ---
-    // 这里我们不使用简单的“熟悉度”，而是采用更现代的 SRS 算法（如 FSRS）中的概念
-    // 这会让你的 App 在记忆算法上更胜一筹
-    pub stability: f32,                                     // 记忆稳定性 (天)
-    pub difficulty: f32,                                    // 单词难度 (0-1)
-    pub due: chrono::DateTime<chrono::Utc>,                 // 到期复习时间
-    pub last_review: Option<chrono::DateTime<chrono::Utc>>, // 上次复习时间
-    // --- 附加信息 ---
-    pub notes: Option<String>,                     // 用户笔记
-    pub created_at: chrono::DateTime<chrono::Utc>, // 创建时间
-}
+use crate::entities::word_inflection;
+use crate::entities::word_phrase;
+use crate::entities::word_sense;
 
 #[derive(Debug, Serialize)]
 pub struct ReviewCard {
@@ -27,6 +15,7 @@ pub struct ReviewCard {
     pub stability: f32,
     pub difficulty: f32,
     pub due: String, //时间序列为字符串
+    pub state: CardState,
 
     // 来自 master_words 的内容信息
     pub master_id: i32,
@@ -34,26 +23,76 @@ pub struct ReviewCard {
     pub audio_url: Option<String>,
     pub definition: String,
     pub pronunciation: Option<String>,
+    pub pronunciation_us: Option<String>,         // 美式音标
+    pub pronunciation_uk: Option<String>,         // 英式音标
+    pub senses: Vec<word_sense::Model>,           // 按词性拆开的释义
+    pub phrases: Vec<word_phrase::Model>,         // 短语
+    pub inflections: Vec<word_inflection::Model>, // 变形 (过去式、复数等)
+}
+
+// 一个单词的结构化释义
+#[derive(Debug, Default)]
+pub struct WordDetails {
+    pub senses: Vec<word_sense::Model>,
+    pub phrases: Vec<word_phrase::Model>,
+    pub inflections: Vec<word_inflection::Model>,
 }
 
 impl ReviewCard {
     pub fn from_query_result(
         user: user_word::Model,
         master: Option<master_word::Model>,
+        details: &mut HashMap<i32, WordDetails>,
     ) -> Option<Self> {
         let master = master?;
+        let details = details.remove(&master.id).unwrap_or_default();
 
         Some(Self {
             id: user.id,
             stability: user.stability,
             difficulty: user.difficulty,
             due: user.due.to_rfc3339(), // 转为 ISO 时间字符串
+            state: CardState::from_i16(user.status).unwrap_or(CardState::New),
 
             master_id: master.id,
             text: master.text,
             audio_url: master.audio_url,
             definition: master.definition,
             pronunciation: master.pronunciation,
+            pronunciation_us: master.pronunciation_us,
+            pronunciation_uk: master.pronunciation_uk,
+            senses: details.senses,
+            phrases: details.phrases,
+            inflections: details.inflections,
         })
     }
 }
+
+// 单词列表里的一行：单词本身加上学习进度，没在学的单词进度字段为空
+#[derive(Debug, Serialize)]
+pub struct WordEntry {
+    #[serde(flatten)]
+    pub word: master_word::Model,
+    pub user_word_id: Option<i32>,
+    pub state: Option<CardState>,
+    pub stability: Option<f32>,
+    pub due: Option<String>, // 下次复习时间，ISO 字符串
+    pub books: Vec<Source>,  // 单词属于哪些词书
+}
+
+impl WordEntry {
+    pub fn from_query_result(
+        master: master_word::Model,
+        user: Option<user_word::Model>,
+        books: Vec<Source>,
+    ) -> Self {
+        Self {
+            word: master,
+            user_word_id: user.as_ref().map(|u| u.id),
+            state: user.as_ref().and_then(|u| CardState::from_i16(u.status)),
+            stability: user.as_ref().map(|u| u.stability),
+            due: user.map(|u| u.due.to_rfc3339()),
+            books,
+        }
+    }
+}
@@ -0,0 +1,185 @@
+// 英语词形还原：把变形后的单词猜回原形 (went -> go, studies -> study)
+// 不规则变形和词典导入的变形存在 word_inflection 表，这里只按规则猜表里没有的情况
+
+// 常见的不规则变形：(变形, 原形, 变形种类)，启动和导入单词后写进 word_inflection 表
+pub const IRREGULAR: [(&str, &str, &str); 64] = [
+    ("am", "be", "present"),
+    ("is", "be", "third_person"),
+    ("are", "be", "present"),
+    ("was", "be", "past"),
+    ("were", "be", "past"),
+    ("been", "be", "past_participle"),
+    ("has", "have", "third_person"),
+    ("had", "have", "past"),
+    ("did", "do", "past"),
+    ("done", "do", "past_participle"),
+    ("does", "do", "third_person"),
+    ("went", "go", "past"),
+    ("gone", "go", "past_participle"),
+    ("goes", "go", "third_person"),
+    ("came", "come", "past"),
+    ("saw", "see", "past"),
+    ("seen", "see", "past_participle"),
+    ("took", "take", "past"),
+    ("taken", "take", "past_participle"),
+    ("gave", "give", "past"),
+    ("given", "give", "past_participle"),
+    ("made", "make", "past"),
+    ("got", "get", "past"),
+    ("gotten", "get", "past_participle"),
+    ("knew", "know", "past"),
+    ("known", "know", "past_participle"),
+    ("thought", "think", "past"),
+    ("told", "tell", "past"),
+    ("said", "say", "past"),
+    ("found", "find", "past"),
+    ("left", "leave", "past"),
+    ("felt", "feel", "past"),
+    ("brought", "bring", "past"),
+    ("bought", "buy", "past"),
+    ("taught", "teach", "past"),
+    ("caught", "catch", "past"),
+    ("began", "begin", "past"),
+    ("begun", "begin", "past_participle"),
+    ("wrote", "write", "past"),
+    ("written", "write", "past_participle"),
+    ("ate", "eat", "past"),
+    ("eaten", "eat", "past_participle"),
+    ("ran", "run", "past"),
+    ("spoke", "speak", "past"),
+    ("spoken", "speak", "past_participle"),
+    ("children", "child", "plural"),
+    ("men", "man", "plural"),
+    ("women", "woman", "plural"),
+    ("people", "person", "plural"),
+    ("feet", "foot", "plural"),
+    ("teeth", "tooth", "plural"),
+    ("mice", "mouse", "plural"),
+    ("geese", "goose", "plural"),
+    ("lives", "life", "plural"),
+    ("wives", "wife", "plural"),
+    ("knives", "knife", "plural"),
+    ("leaves", "leaf", "plural"),
+    ("better", "good", "comparative"),
+    ("best", "good", "superlative"),
+    ("worse", "bad", "comparative"),
+    ("worst", "bad", "superlative"),
+    ("more", "many", "comparative"),
+    ("most", "many", "superlative"),
+    ("less", "little", "comparative"),
+];
+
+// 以 s 结尾但不是复数或第三人称的常见词，去掉 s 会得到别的单词 (news -> new)
+const NOT_INFLECTED: [&str; 15] = [
+    "news",
+    "means",
+    "series",
+    "species",
+    "physics",
+    "economics",
+    "politics",
+    "mathematics",
+    "always",
+    "perhaps",
+    "this",
+    "his",
+    "its",
+    "thus",
+    "yes",
+];
+
+// 去掉重复的辅音：stopp -> stop，runn -> run，英式拼写 travell -> travel
+fn undouble(stem: &str) -> Option<String> {
+    let bytes = stem.as_bytes();
+    let n = bytes.len();
+    (n >= 3 && bytes[n - 1] == bytes[n - 2] && !b"aeiousz".contains(&bytes[n - 1]))
+        .then(|| stem[..n - 1].to_owned())
+}
+
+// 按规则猜可能的原形，越靠前越可能；不包括单词本身
+pub fn lemma_candidates(word: &str) -> Vec<String> {
+    let word = word.trim().to_lowercase();
+    let mut candidates: Vec<String> = Vec::new();
+    if !word.chars().all(|c| c.is_ascii_alphabetic()) || NOT_INFLECTED.contains(&word.as_str()) {
+        return candidates;
+    }
+
+    // (后缀, 换成什么)，同一个后缀可能有几种还原方式
+    let rules: [(&str, &[&str]); 9] = [
+        ("ies", &["y"]),
+        ("ied", &["y"]),
+        ("iest", &["y"]),
+        ("ier", &["y"]),
+        ("es", &["", "e"]),
+        ("s", &[""]),
+        ("ing", &["", "e"]),
+        ("ed", &["", "e"]),
+        ("est", &["", "e"]),
+    ];
+    for (suffix, replacements) in rules {
+        let Some(stem) = word.strip_suffix(suffix) else {
+            continue;
+        };
+        if stem.len() < 2 || (suffix == "s" && stem.ends_with('s')) {
+            continue;
+        }
+        for r in replacements {
+            candidates.push(format!("{}{}", stem, r));
+        }
+        if let Some(s) = undouble(stem) {
+            candidates.push(s);
+        }
+    }
+    // 比较级：bigger -> big，nicer -> nice；放最后，避免 water -> wat 之类排在前面
+    if let Some(stem) = word.strip_suffix("er") {
+        if stem.len() >= 2 {
+            candidates.push(stem.to_owned());
+            candidates.push(format!("{}e", stem));
+            if let Some(s) = undouble(stem) {
+                candidates.push(s);
+            }
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    candidates.retain(|c| *c != word && seen.insert(c.clone()));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regular_suffixes() {
+        assert_eq!(lemma_candidates("studies")[0], "study");
+        assert_eq!(lemma_candidates("Boxes")[..2], ["box", "boxe"]);
+        assert!(lemma_candidates("making").contains(&"make".to_owned()));
+        assert!(lemma_candidates("happiest").contains(&"happy".to_owned()));
+        assert!(lemma_candidates("bigger").contains(&"big".to_owned()));
+    }
+
+    #[test]
+    fn doubled_consonants() {
+        assert!(lemma_candidates("stopped").contains(&"stop".to_owned()));
+        assert!(lemma_candidates("running").contains(&"run".to_owned()));
+        assert!(lemma_candidates("travelled").contains(&"travel".to_owned()));
+        // 本来就是双写的不去掉
+        assert!(!lemma_candidates("passed").contains(&"pas".to_owned()));
+    }
+
+    #[test]
+    fn words_that_only_look_inflected() {
+        assert!(lemma_candidates("news").is_empty());
+        assert!(lemma_candidates("this").is_empty());
+        assert!(lemma_candidates("glass").is_empty());
+        assert!(lemma_candidates("e-mails").is_empty());
+    }
+
+    #[test]
+    fn irregular_forms_are_not_guessed() {
+        // 不规则变形查 word_inflection 表，规则猜不出来
+        assert!(!lemma_candidates("went").contains(&"go".to_owned()));
+        assert!(IRREGULAR.iter().all(|(form, lemma, _)| form != lemma));
+    }
+}
@@ -0,0 +1,142 @@
+use crate::algorithm::{self, Rating};
+
+// 参与训练的最少预测次数，数据太少拟合出来的参数没有意义
+pub const MIN_PREDICTIONS: usize = 50;
+
+const ITERATIONS: usize = 200;
+const LEARNING_RATE: f32 = 0.02;
+const EPSILON: f32 = 1e-3;
+// 向默认参数的 L2 正则，防止复习记录少时过拟合
+const REGULARIZATION: f32 = 0.001;
+
+// 每个参数的取值范围 (与 FSRS-5 官方实现一致)
+const BOUNDS: [(f32, f32); 19] = [
+    (0.01, 100.0),
+    (0.01, 100.0),
+    (0.01, 100.0),
+    (0.01, 100.0),
+    (1.0, 10.0),
+    (0.001, 4.0),
+    (0.001, 4.0),
+    (0.001, 0.75),
+    (0.0, 4.5),
+    (0.0, 0.8),
+    (0.001, 3.5),
+    (0.001, 5.0),
+    (0.001, 0.25),
+    (0.001, 0.9),
+    (0.0, 4.0),
+    (0.0, 1.0),
+    (1.0, 6.0),
+    (0.0, 2.0),
+    (0.0, 2.0),
+];
+
+// 一个单词按时间排列的复习记录: (评分, 距离上次复习的天数)
+pub type ReviewSequence = Vec<(Rating, f32)>;
+
+pub struct OptimizeResult {
+    pub params: [f32; 19],
+    pub loss_before: f32,
+    pub loss_after: f32,
+    pub predictions: usize,
+}
+
+// 用给定参数重放所有复习记录，返回预测"记得"的平均对数损失和预测次数
+// 只有间隔超过一天的复习才参与预测，同一天内的复习只用来推进记忆状态
+pub fn log_loss(w: &[f32; 19], sequences: &[ReviewSequence]) -> (f32, usize) {
+    let mut total = 0.0f64;
+    let mut count = 0usize;
+
+    for seq in sequences {
+        let mut s = 0.0;
+        let mut d = 0.0;
+        for &(rating, elapsed_days) in seq {
+            if s > 0.0 && elapsed_days >= 1.0 {
+                let r = algorithm::retrievability(elapsed_days, s).clamp(1e-4, 1.0 - 1e-4) as f64;
+                let recalled = !matches!(rating, Rating::Again);
+                total -= if recalled { r.ln() } else { (1.0 - r).ln() };
+                count += 1;
+            }
+            (s, d) = algorithm::next_memory_state(w, s, d, elapsed_days, rating);
+        }
+    }
+
+    if count == 0 {
+        return (0.0, 0);
+    }
+    ((total / count as f64) as f32, count)
+}
+
+fn objective(w: &[f32; 19], sequences: &[ReviewSequence]) -> f32 {
+    let penalty: f32 = w
+        .iter()
+        .zip(algorithm::DEFAULT_PARAMS.iter())
+        .map(|(a, b)| (a - b).powi(2))
+        .sum();
+    log_loss(w, sequences).0 + REGULARIZATION * penalty
+}
+
+// 把参数截断到 BOUNDS 范围内
+pub fn clamp_params(w: &mut [f32; 19]) {
+    for (v, (lo, hi)) in w.iter_mut().zip(BOUNDS.iter()) {
+        *v = v.clamp(*lo, *hi);
+    }
+}
+
+// 从 initial 出发，用 Adam + 数值梯度最小化对数损失
+pub fn optimize(initial: &[f32; 19], sequences: &[ReviewSequence]) -> OptimizeResult {
+    let (loss_before, predictions) = log_loss(initial, sequences);
+    if predictions < MIN_PREDICTIONS {
+        return OptimizeResult {
+            params: *initial,
+            loss_before,
+            loss_after: loss_before,
+            predictions,
+        };
+    }
+
+    let (beta1, beta2) = (0.9f32, 0.999f32);
+    let mut w = *initial;
+    let mut m = [0.0f32; 19];
+    let mut v = [0.0f32; 19];
+
+    for t in 1..=ITERATIONS {
+        let mut grad = [0.0f32; 19];
+        for i in 0..19 {
+            let mut plus = w;
+            let mut minus = w;
+            plus[i] += EPSILON;
+            minus[i] -= EPSILON;
+            grad[i] =
+                (objective(&plus, sequences) - objective(&minus, sequences)) / (2.0 * EPSILON);
+        }
+
+        for i in 0..19 {
+            m[i] = beta1 * m[i] + (1.0 - beta1) * grad[i];
+            v[i] = beta2 * v[i] + (1.0 - beta2) * grad[i] * grad[i];
+            let m_hat = m[i] / (1.0 - beta1.powi(t as i32));
+            let v_hat = v[i] / (1.0 - beta2.powi(t as i32));
+            w[i] -= LEARNING_RATE * m_hat / (v_hat.sqrt() + 1e-8);
+        }
+        clamp_params(&mut w);
+    }
+
+    let (loss_after, _) = log_loss(&w, sequences);
+    // 没有变好就保留原参数
+    if !loss_after.is_finite() || loss_after >= loss_before {
+        return OptimizeResult {
+            params: *initial,
+            loss_before,
+            loss_after: loss_before,
+            predictions,
+        };
+    }
+
+    OptimizeResult {
+        params: w,
+        loss_before,
+        loss_after,
+        predictions,
+    }
+}
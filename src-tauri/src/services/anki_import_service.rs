@@ -0,0 +1,710 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ConnectionTrait, Database, DatabaseConnection, DbErr,
+    EntityTrait, FromQueryResult, QuerySelect, Statement, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{Cursor, Read},
+    path::Path,
+};
+use zip::ZipArchive;
+
+use crate::{
+    algorithm::{self, Rating},
+    clock::Clock,
+    db::{self, JsonTranslation, JsonWord},
+    entities::{
+        card_state::CardState,
+        prelude::{MasterWord, UserWord},
+        source_enum::Source,
+        user_word,
+    },
+    services::{
+        book_service, fsrs_params_service,
+        import_service::{self, InvalidRow},
+    },
+};
+
+// 猜音标字段时用的字段名 (小写)
+const PRONUNCIATION_FIELDS: [&str; 5] = ["pronunciation", "phonetic", "ipa", "音标", "发音"];
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AnkiImportOptions {
+    pub text_field: Option<String>,       // 单词所在字段名，默认第一个字段
+    pub definition_field: Option<String>, // 释义所在字段名，默认第二个字段
+    pub pronunciation_field: Option<String>, // 音标所在字段名，默认按字段名猜
+    pub import_history: bool,             // 是否把 Anki 的复习记录换算成学习进度
+    pub source: Option<Source>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct AnkiImportReport {
+    pub inserted: u32,
+    pub skipped: Vec<String>, // 词库里已有或牌组里重复的单词
+    pub invalid: Vec<InvalidRow>,
+    pub media_copied: u32,
+    pub scheduled: u32, // 带着复习进度导入的单词数
+}
+
+#[derive(Debug, FromQueryResult)]
+struct AnkiCollection {
+    crt: i64,
+    conf: String,
+    models: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct AnkiConf {
+    sched_ver: Option<i32>, // 没有这个字段就是旧的 v1 调度器
+}
+
+#[derive(Debug, FromQueryResult)]
+struct AnkiNote {
+    id: i64,
+    mid: i64,
+    flds: String,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct AnkiCard {
+    id: i64,
+    nid: i64,
+    card_type: i32,
+    queue: i32,
+    due: i64,
+    ivl: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct AnkiReview {
+    id: i64, // 复习时间 (毫秒时间戳)
+    cid: i64,
+    ease: i32,
+    review_type: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnkiModel {
+    flds: Vec<AnkiField>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnkiField {
+    name: String,
+}
+
+fn zip_err(e: zip::result::ZipError) -> DbErr {
+    DbErr::Custom(format!("无法读取 apkg 文件: {}", e))
+}
+
+fn io_err(e: std::io::Error) -> DbErr {
+    DbErr::Custom(e.to_string())
+}
+
+// 去掉 HTML 标签和 [sound:...]，<br> / <div> 换成换行
+fn strip_html(s: &str) -> String {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find(['<', '[']) {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let (close, is_tag) = if rest.starts_with('<') {
+            ('>', true)
+        } else if rest.starts_with("[sound:") {
+            (']', false)
+        } else {
+            out.push('[');
+            rest = &rest[1..];
+            continue;
+        };
+        let Some(end) = rest.find(close) else {
+            break;
+        };
+        if is_tag {
+            let tag = rest[1..end].trim_start_matches('/').to_lowercase();
+            if tag.starts_with("br") || tag.starts_with("div") || tag.starts_with('p') {
+                out.push('\n');
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+
+    out.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// 第一个 [sound:xxx.mp3] 里的文件名
+fn sound_file(s: &str) -> Option<&str> {
+    let start = s.find("[sound:")? + "[sound:".len();
+    let end = s[start..].find(']')?;
+    Some(s[start..start + end].trim())
+}
+
+fn field_index(names: &[String], wanted: Option<&str>, fallback: Option<usize>) -> Option<usize> {
+    match wanted {
+        Some(w) => names.iter().position(|n| n.eq_ignore_ascii_case(w)),
+        None => fallback,
+    }
+}
+
+// 把 Anki 笔记的字段拼成 JsonWord，缺单词或释义时返回 None
+fn note_to_word(
+    fields: &[&str],
+    names: &[String],
+    options: &AnkiImportOptions,
+) -> Option<JsonWord> {
+    let text_idx = field_index(names, options.text_field.as_deref(), Some(0))?;
+    let def_idx = field_index(names, options.definition_field.as_deref(), Some(1))?;
+    let guessed = names
+        .iter()
+        .position(|n| PRONUNCIATION_FIELDS.contains(&n.to_lowercase().as_str()));
+    let pron_idx = field_index(names, options.pronunciation_field.as_deref(), guessed);
+
+    let word = strip_html(fields.get(text_idx)?);
+    let translations: Vec<JsonTranslation> = strip_html(fields.get(def_idx)?)
+        .lines()
+        .map(|line| {
+            let (word_type, translation) = import_service::split_pos(line);
+            JsonTranslation {
+                translation,
+                word_type,
+            }
+        })
+        .collect();
+    if word.is_empty() || word.contains('\n') || translations.is_empty() {
+        return None;
+    }
+
+    Some(JsonWord {
+        word,
+        translations,
+        phrases: None,
+        pronunciation: pron_idx
+            .and_then(|i| fields.get(i))
+            .map(|p| strip_html(p))
+            .filter(|p| !p.is_empty()),
+    })
+}
+
+// 把 apkg 里的音频复制到 audio_dir (应用数据目录下的 audio)，返回 audio_url
+// 牌组里没打包这个文件时返回 None，读写失败时报错
+fn copy_media<R: Read + std::io::Seek>(
+    archive: &mut ZipArchive<R>,
+    media: &HashMap<String, String>,
+    file_name: &str,
+    audio_dir: &Path,
+) -> Result<Option<String>, DbErr> {
+    // 文件名只保留最后一段，防止写到目录外面
+    let Some(safe_name) = Path::new(file_name).file_name().and_then(|n| n.to_str()) else {
+        return Ok(None);
+    };
+    let Some(entry) = media.get(file_name) else {
+        return Ok(None);
+    };
+    let mut source = archive.by_name(entry).map_err(zip_err)?;
+
+    let copy_err = |e: std::io::Error| DbErr::Custom(format!("无法复制音频 {}: {}", safe_name, e));
+    fs::create_dir_all(audio_dir).map_err(copy_err)?;
+    let mut target = fs::File::create(audio_dir.join(safe_name)).map_err(copy_err)?;
+    std::io::copy(&mut source, &mut target).map_err(copy_err)?;
+    Ok(Some(format!("audio/{}", safe_name)))
+}
+
+// Anki 的按键换成评分；v1 调度器学习中 (type 0) 和重学 (type 2) 只有三个按钮
+fn anki_rating(ease: i32, review_type: i32, sched_v1: bool) -> Option<Rating> {
+    match (sched_v1 && matches!(review_type, 0 | 2), ease) {
+        (true, 1) => Some(Rating::Again),
+        (true, 2) => Some(Rating::Good),
+        (true, 3) => Some(Rating::Easy),
+        (true, _) => None,
+        (false, ease) => Rating::from_i32(ease),
+    }
+}
+
+// 卡片的到期时间：queue 2/3 是牌组创建后的第几天，queue 1/4 是时间戳 (秒)
+// 暂停或搁置的卡片 queue 不再说明含义，按 type 和数值大小判断
+fn anki_due(card: &AnkiCard, collection_created: i64) -> Option<DateTime<Utc>> {
+    let is_day_number = match card.queue {
+        2 | 3 => true,
+        1 | 4 => false,
+        _ => card.card_type == 2 || card.due < 1_000_000_000,
+    };
+    if is_day_number {
+        Some(Utc.timestamp_opt(collection_created, 0).single()? + Duration::days(card.due))
+    } else {
+        Utc.timestamp_opt(card.due, 0).single()
+    }
+}
+
+// 用 Anki 的复习记录重放一遍 FSRS，得到当前的记忆状态
+// 新卡 (type 0) 不管是否暂停都不带进度，和没学过的单词一样
+fn replay_history(
+    w: &[f32; 19],
+    card: &AnkiCard,
+    reviews: &[AnkiReview],
+    collection: &AnkiCollection,
+    master_id: i32,
+    now: DateTime<Utc>,
+) -> Option<user_word::ActiveModel> {
+    let resume_state = match card.card_type {
+        1 => CardState::Learning,
+        2 => CardState::Review,
+        3 => CardState::Relearning,
+        _ => return None,
+    };
+    // 暂停的卡片记住暂停前的状态，恢复时用
+    let (state, resume_status) = match card.queue {
+        -1 => (CardState::Suspended, Some(resume_state.as_i16())),
+        _ => (resume_state, None),
+    };
+    let sched_v1 = serde_json::from_str::<AnkiConf>(&collection.conf)
+        .unwrap_or_default()
+        .sched_ver
+        .unwrap_or(1)
+        < 2;
+
+    let mut stability = 0.0;
+    let mut difficulty = 0.0;
+    let mut last_review: Option<DateTime<Utc>> = None;
+    // type 4 是手动改期，不算真正的复习
+    for review in reviews.iter().filter(|r| r.review_type != 4) {
+        let Some(rating) = anki_rating(review.ease, review.review_type, sched_v1) else {
+            continue;
+        };
+        let reviewed_at = Utc.timestamp_millis_opt(review.id).single()?;
+        let elapsed_days = last_review
+            .map(|t| (reviewed_at - t).num_seconds() as f32 / 86400.0)
+            .unwrap_or(0.0);
+        (stability, difficulty) =
+            algorithm::next_memory_state(w, stability, difficulty, elapsed_days, rating);
+        last_review = Some(reviewed_at);
+    }
+
+    // 没有复习记录 (比如被清理过)，就用当前间隔近似
+    if last_review.is_none() {
+        stability = (card.ivl.max(1)) as f32;
+        difficulty = algorithm::initial_difficulty(w, Rating::Good);
+    }
+
+    let due = anki_due(card, collection.crt).unwrap_or(now);
+
+    Some(user_word::ActiveModel {
+        master_word_id: Set(master_id),
+        stability: Set(stability),
+        difficulty: Set(difficulty),
+        due: Set(due.into()),
+        last_review: Set(last_review.map(Into::into)),
+        status: Set(state.as_i16()),
+        resume_status: Set(resume_status),
+        added_at: Set(now.into()),
+        learning_step: Set(0),
+        ..Default::default()
+    })
+}
+
+async fn query_all<T: FromQueryResult>(
+    conn: &DatabaseConnection,
+    sql: &str,
+) -> Result<Vec<T>, DbErr> {
+    T::find_by_statement(Statement::from_string(conn.get_database_backend(), sql))
+        .all(conn)
+        .await
+}
+
+// 读出 apkg 里的集合数据库：先解压到临时文件，再用 SQLite 打开
+async fn open_collection<R: Read + std::io::Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<(DatabaseConnection, std::path::PathBuf), DbErr> {
+    let name = ["collection.anki21", "collection.anki2"]
+        .into_iter()
+        .find(|n| archive.index_for_name(n).is_some())
+        .ok_or_else(|| {
+            DbErr::Custom("不支持的 apkg 格式，请在 Anki 导出时勾选 \"兼容旧版本\"".to_owned())
+        })?;
+
+    let tmp_path = std::env::temp_dir().join(format!(
+        "yaya-anki-{}.db",
+        Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+    let copied = {
+        let mut source = archive.by_name(name).map_err(zip_err)?;
+        fs::File::create(&tmp_path).and_then(|mut target| std::io::copy(&mut source, &mut target))
+    };
+    let opened = match copied {
+        Ok(_) => {
+            let url = format!("sqlite://{}?mode=ro", tmp_path.to_string_lossy());
+            Database::connect(&url).await
+        }
+        Err(e) => Err(io_err(e)),
+    };
+
+    // 打不开 (比如文件损坏) 时临时文件也要删掉
+    match opened {
+        Ok(conn) => Ok((conn, tmp_path)),
+        Err(e) => {
+            let _ = fs::remove_file(&tmp_path);
+            Err(e)
+        }
+    }
+}
+
+//导入 Anki 牌组 (.apkg)：笔记 -> 主词库，音频 -> audio_dir，可选换算复习进度
+pub async fn import_apkg(
+    db: &DatabaseConnection,
+    clock: &dyn Clock,
+    data: Vec<u8>,
+    audio_dir: &Path,
+    options: AnkiImportOptions,
+) -> Result<AnkiImportReport, DbErr> {
+    let mut archive = ZipArchive::new(Cursor::new(data)).map_err(zip_err)?;
+    let (anki, tmp_path) = open_collection(&mut archive).await?;
+    let result = import_collection(db, clock, &anki, &mut archive, audio_dir, options).await;
+
+    anki.close().await?;
+    let _ = fs::remove_file(tmp_path);
+    result
+}
+
+async fn import_collection<R: Read + std::io::Seek>(
+    db: &DatabaseConnection,
+    clock: &dyn Clock,
+    anki: &DatabaseConnection,
+    archive: &mut ZipArchive<R>,
+    audio_dir: &Path,
+    options: AnkiImportOptions,
+) -> Result<AnkiImportReport, DbErr> {
+    let now = clock.now();
+    let collection = query_all::<AnkiCollection>(anki, "SELECT crt, conf, models FROM col")
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| DbErr::Custom("apkg 里没有牌组信息".to_owned()))?;
+    let models: HashMap<String, AnkiModel> = serde_json::from_str(&collection.models)
+        .map_err(|e| DbErr::Custom(format!("牌组模板格式错误: {}", e)))?;
+    let notes = query_all::<AnkiNote>(anki, "SELECT id, mid, flds FROM notes ORDER BY id").await?;
+
+    // media 文件是 {"0": "apple.mp3"}，反过来按文件名查压缩包里的条目
+    let media: HashMap<String, String> = match archive.by_name("media") {
+        Ok(mut f) => {
+            let mut raw = String::new();
+            f.read_to_string(&mut raw).map_err(io_err)?;
+            serde_json::from_str::<HashMap<String, String>>(&raw)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(entry, name)| (name, entry))
+                .collect()
+        }
+        Err(_) => HashMap::new(),
+    };
+
+    // 每个笔记只看第一张卡片 (正面) 的进度
+    let mut cards: HashMap<i64, AnkiCard> = HashMap::new();
+    let mut reviews: HashMap<i64, Vec<AnkiReview>> = HashMap::new();
+    // 已经在学的单词保留本地进度，不用牌组里的覆盖
+    let mut learned: HashSet<i32> = HashSet::new();
+    if options.import_history {
+        let rows = query_all::<AnkiCard>(
+            anki,
+            "SELECT id, nid, type AS card_type, queue, due, ivl FROM cards ORDER BY nid, ord",
+        )
+        .await?;
+        for card in rows {
+            cards.entry(card.nid).or_insert(card);
+        }
+        let rows = query_all::<AnkiReview>(
+            anki,
+            "SELECT id, cid, ease, type AS review_type FROM revlog ORDER BY cid, id",
+        )
+        .await?;
+        for review in rows {
+            reviews.entry(review.cid).or_default().push(review);
+        }
+        learned = UserWord::find()
+            .select_only()
+            .column(user_word::Column::MasterWordId)
+            .into_tuple::<i32>()
+            .all(db)
+            .await?
+            .into_iter()
+            .collect();
+    }
+
+    let params = fsrs_params_service::load_params(db, None).await?;
+    let source = options.source.clone().unwrap_or(Source::Custom);
+    let mut seen = import_service::existing_texts(db).await?;
+    let mut report = AnkiImportReport::default();
+    let mut senses = Vec::new();
+    let mut phrases = Vec::new();
+    let mut members = Vec::new();
+    let txn = db.begin().await?;
+    let book_id = book_service::book_id(&txn, &source).await?;
+
+    for (i, note) in notes.iter().enumerate() {
+        let names: Vec<String> = models
+            .get(&note.mid.to_string())
+            .map(|m| m.flds.iter().map(|f| f.name.clone()).collect())
+            .unwrap_or_default();
+        let fields: Vec<&str> = note.flds.split('\u{1f}').collect();
+
+        let Some(word) = note_to_word(&fields, &names, &options) else {
+            report.invalid.push(InvalidRow {
+                line: i + 1,
+                content: fields.join(" | "),
+                reason: "缺少单词或释义".to_owned(),
+            });
+            continue;
+        };
+        // 已有的单词只加入词书，还没开始学的话照样带上复习进度
+        let key = word.word.to_lowercase();
+        let master_id = match seen.get(&key) {
+            Some(&id) => {
+                report.skipped.push(word.word);
+                id
+            }
+            None => {
+                let mut active_model = import_service::new_master_word(&word);
+                if let Some(name) = fields.iter().find_map(|f| sound_file(f)) {
+                    if let Some(audio_url) = copy_media(archive, &media, name, audio_dir)? {
+                        active_model.audio_url = Set(Some(audio_url));
+                        report.media_copied += 1;
+                    }
+                }
+                let master_id = MasterWord::insert(active_model)
+                    .exec(&txn)
+                    .await?
+                    .last_insert_id;
+                seen.insert(key, master_id);
+
+                let (s, p) = db::senses_and_phrases(&word, master_id);
+                senses.extend(s);
+                phrases.extend(p);
+                report.inserted += 1;
+                master_id
+            }
+        };
+        members.push((master_id, i as i32));
+
+        let Some(card) = cards.get(&note.id) else {
+            continue;
+        };
+        if learned.contains(&master_id) {
+            continue;
+        }
+        let history = reviews.get(&card.id).map(Vec::as_slice).unwrap_or_default();
+        if let Some(user) = replay_history(&params, card, history, &collection, master_id, now) {
+            user.insert(&txn).await?;
+            learned.insert(master_id);
+            report.scheduled += 1;
+        }
+    }
+
+    db::insert_details(&txn, senses, phrases).await?;
+    book_service::add_to_book(&txn, book_id, &members).await?;
+    txn.commit().await?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clock::FixedClock, services::user_word_service};
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::{ColumnTrait, QueryFilter};
+    use std::io::Write;
+    use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+    const CRT: i64 = 1_700_000_000; // 牌组创建时间
+
+    // 造一个最小的 apkg：三个笔记，一个音频
+    async fn fixture() -> Vec<u8> {
+        let db_path = std::env::temp_dir().join(format!(
+            "yaya-anki-fixture-{}.db",
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let url = format!("sqlite://{}?mode=rwc", db_path.to_string_lossy());
+        let anki = Database::connect(&url).await.unwrap();
+        let models = r#"{"1": {"flds": [{"name": "Front"}, {"name": "Back"}]}}"#;
+        let sql = format!(
+            "CREATE TABLE col (crt INTEGER, conf TEXT, models TEXT);
+             CREATE TABLE notes (id INTEGER PRIMARY KEY, mid INTEGER, flds TEXT);
+             CREATE TABLE cards (id INTEGER PRIMARY KEY, nid INTEGER, ord INTEGER,
+                 type INTEGER, queue INTEGER, due INTEGER, ivl INTEGER);
+             CREATE TABLE revlog (id INTEGER PRIMARY KEY, cid INTEGER, ease INTEGER, type INTEGER);
+             INSERT INTO col VALUES ({CRT}, '{{}}', '{models}');
+             INSERT INTO notes VALUES
+                 (1, 1, 'apple[sound:apple.mp3]' || char(31) || 'n. 苹果'),
+                 (2, 1, 'banana' || char(31) || 'n. 香蕉'),
+                 (3, 1, 'cherry' || char(31) || 'n. 樱桃');
+             -- apple: 复习卡，due 是第 10 天
+             -- banana: 暂停的新卡
+             -- cherry: 隔天的重学卡 (queue 3)，due 是第 5 天
+             INSERT INTO cards VALUES
+                 (11, 1, 0, 2, 2, 10, 3),
+                 (12, 2, 0, 0, -1, 2, 0),
+                 (13, 3, 0, 3, 3, 5, 1);
+             -- v1 调度器：学习中按 3 是 Easy
+             INSERT INTO revlog VALUES
+                 ({r1}, 11, 3, 0),
+                 ({r2}, 11, 3, 1),
+                 ({r1} + 1, 13, 2, 1),
+                 ({r2} + 1, 13, 1, 1);",
+            r1 = (CRT + 86400) * 1000,
+            r2 = (CRT + 4 * 86400) * 1000,
+        );
+        anki.execute_unprepared(&sql).await.unwrap();
+        anki.close().await.unwrap();
+        let collection = fs::read(&db_path).unwrap();
+        let _ = fs::remove_file(db_path);
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        for (name, data) in [
+            ("collection.anki2", collection.as_slice()),
+            ("media", br#"{"0": "apple.mp3"}"#.as_slice()),
+            ("0", b"ID3".as_slice()),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn v1_learning_eases_have_three_buttons() {
+        assert_eq!(anki_rating(2, 0, true), Some(Rating::Good));
+        assert_eq!(anki_rating(3, 2, true), Some(Rating::Easy));
+        assert_eq!(anki_rating(4, 0, true), None);
+        // 复习卡在 v1 里也是四个按钮
+        assert_eq!(anki_rating(3, 1, true), Some(Rating::Good));
+        assert_eq!(anki_rating(2, 0, false), Some(Rating::Hard));
+    }
+
+    #[test]
+    fn import_apkg_fixture() {
+        tauri::async_runtime::block_on(async {
+            let db = Database::connect("sqlite::memory:").await.unwrap();
+            Migrator::up(&db, None).await.unwrap();
+            let clock = FixedClock(Utc.timestamp_opt(CRT + 30 * 86400, 0).unwrap());
+            let audio_dir = std::env::temp_dir().join(format!(
+                "yaya-anki-audio-{}",
+                Utc::now().timestamp_nanos_opt().unwrap_or_default()
+            ));
+            let options = AnkiImportOptions {
+                import_history: true,
+                ..Default::default()
+            };
+
+            let report = import_apkg(&db, &clock, fixture().await, &audio_dir, options)
+                .await
+                .unwrap();
+            assert_eq!(report.inserted, 3);
+            assert_eq!(report.media_copied, 1);
+            assert!(audio_dir.join("apple.mp3").exists());
+            // 暂停的新卡不带进度
+            assert_eq!(report.scheduled, 2);
+
+            let master = MasterWord::find().all(&db).await.unwrap();
+            let id_of = |text: &str| master.iter().find(|m| m.text == text).unwrap().id;
+            let apple = master.iter().find(|m| m.text == "apple").unwrap();
+            assert_eq!(apple.audio_url.as_deref(), Some("audio/apple.mp3"));
+
+            let card_of = |id: i32| {
+                UserWord::find()
+                    .filter(user_word::Column::MasterWordId.eq(id))
+                    .one(&db)
+            };
+            assert!(card_of(id_of("banana")).await.unwrap().is_none());
+
+            let apple = card_of(id_of("apple")).await.unwrap().unwrap();
+            assert_eq!(apple.status, CardState::Review.as_i16());
+            assert_eq!(
+                apple.due.with_timezone(&Utc),
+                Utc.timestamp_opt(CRT + 10 * 86400, 0).unwrap()
+            );
+            let w = &algorithm::DEFAULT_PARAMS;
+            let (s, d) = algorithm::next_memory_state(w, 0.0, 0.0, 0.0, Rating::Easy);
+            let (s, _) = algorithm::next_memory_state(w, s, d, 3.0, Rating::Good);
+            assert!((apple.stability - s).abs() < 1e-4);
+
+            let cherry = card_of(id_of("cherry")).await.unwrap().unwrap();
+            assert_eq!(cherry.status, CardState::Relearning.as_i16());
+            assert_eq!(
+                cherry.due.with_timezone(&Utc),
+                Utc.timestamp_opt(CRT + 5 * 86400, 0).unwrap()
+            );
+
+            let _ = fs::remove_dir_all(audio_dir);
+        });
+    }
+
+    #[test]
+    fn existing_words_keep_or_take_progress() {
+        tauri::async_runtime::block_on(async {
+            let db = Database::connect("sqlite::memory:").await.unwrap();
+            Migrator::up(&db, None).await.unwrap();
+            let clock = FixedClock(Utc.timestamp_opt(CRT + 30 * 86400, 0).unwrap());
+            let audio_dir = std::env::temp_dir().join(format!(
+                "yaya-anki-audio-{}",
+                Utc::now().timestamp_nanos_opt().unwrap_or_default()
+            ));
+
+            // apple 已经在词库里但还没学，cherry 已经在学了
+            let mut ids = HashMap::new();
+            for text in ["apple", "cherry"] {
+                let word = JsonWord {
+                    word: text.to_owned(),
+                    translations: Vec::new(),
+                    phrases: None,
+                    pronunciation: None,
+                };
+                let id = MasterWord::insert(import_service::new_master_word(&word))
+                    .exec(&db)
+                    .await
+                    .unwrap()
+                    .last_insert_id;
+                ids.insert(text, id);
+            }
+            let cherry = user_word_service::add_word_to_learning(&db, &clock, ids["cherry"])
+                .await
+                .unwrap();
+
+            let options = AnkiImportOptions {
+                import_history: true,
+                ..Default::default()
+            };
+            let report = import_apkg(&db, &clock, fixture().await, &audio_dir, options)
+                .await
+                .unwrap();
+            assert_eq!(report.inserted, 1);
+            assert_eq!(report.skipped, vec!["apple", "cherry"]);
+            assert_eq!(report.scheduled, 1);
+
+            let card_of = |id: i32| {
+                UserWord::find()
+                    .filter(user_word::Column::MasterWordId.eq(id))
+                    .all(&db)
+            };
+            let apple = card_of(ids["apple"]).await.unwrap();
+            assert_eq!(apple.len(), 1);
+            assert_eq!(apple[0].status, CardState::Review.as_i16());
+            assert_eq!(card_of(ids["cherry"]).await.unwrap(), vec![cherry]);
+
+            let _ = fs::remove_dir_all(audio_dir);
+        });
+    }
+}
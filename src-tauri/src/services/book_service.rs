@@ -0,0 +1,249 @@
+use sea_orm::{
+    sea_query::{Expr, OnConflict, Query, SimpleExpr},
+    ActiveValue::Set,
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, JoinType, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, RelationTrait,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::{
+    books::{self, BookRegistry},
+    entities::{
+        master_word,
+        prelude::{MasterWord, WordBook, WordBookMembership},
+        source_enum::Source,
+        user_word, word_book, word_book_membership,
+    },
+    services::{
+        dictionary_service,
+        import_service::{self, ImportReport},
+        master_word_service,
+    },
+};
+
+#[derive(Debug, Serialize)]
+pub struct BookInfo {
+    pub source: Source,
+    pub name: String,
+    pub available: bool,         // 安装包里有没有这本词书的数据
+    pub word_count: Option<i32>, // 词书里的单词数，还没安装过时为空
+    pub installed_count: u64,    // 词库里已经有多少个属于这本词书的单词
+}
+
+// 词书在 word_book 表里的 id，每个 Source 的记录由迁移建好
+pub async fn book_id<C: ConnectionTrait>(db: &C, source: &Source) -> Result<i32, DbErr> {
+    WordBook::find()
+        .filter(word_book::Column::Code.eq(source.as_ref()))
+        .one(db)
+        .await?
+        .map(|b| b.id)
+        .ok_or_else(|| DbErr::RecordNotFound(format!("词书 {} 不存在", source.as_ref())))
+}
+
+// 记下内置词书的单词数，列出词书时直接用
+pub async fn set_word_count<C: ConnectionTrait>(
+    db: &C,
+    source: &Source,
+    count: usize,
+) -> Result<(), DbErr> {
+    WordBook::update_many()
+        .col_expr(word_book::Column::WordCount, Expr::value(count as i32))
+        .filter(word_book::Column::Code.eq(source.as_ref()))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+// "单词属于其中某本词书" 的条件，codes 是 Source 的字符串形式
+pub fn in_books<S: AsRef<str>>(codes: &[S]) -> SimpleExpr {
+    let codes: Vec<String> = codes.iter().map(|c| c.as_ref().to_owned()).collect();
+    master_word::Column::Id.in_subquery(
+        Query::select()
+            .column((
+                WordBookMembership,
+                word_book_membership::Column::MasterWordId,
+            ))
+            .from(WordBookMembership)
+            .inner_join(
+                WordBook,
+                Expr::col((WordBook, word_book::Column::Id))
+                    .equals((WordBookMembership, word_book_membership::Column::WordBookId)),
+            )
+            .and_where(Expr::col((WordBook, word_book::Column::Code)).is_in(codes))
+            .to_owned(),
+    )
+}
+
+// 单词在这几本词书里最靠前的位置，codes 为空时看所有词书
+pub fn book_order<S: AsRef<str>>(codes: &[S]) -> SimpleExpr {
+    let mut query = Query::select()
+        .expr(Expr::col((WordBookMembership, word_book_membership::Column::SortOrder)).min())
+        .from(WordBookMembership)
+        .inner_join(
+            WordBook,
+            Expr::col((WordBook, word_book::Column::Id))
+                .equals((WordBookMembership, word_book_membership::Column::WordBookId)),
+        )
+        .and_where(
+            Expr::col((
+                WordBookMembership,
+                word_book_membership::Column::MasterWordId,
+            ))
+            .equals((MasterWord, master_word::Column::Id)),
+        )
+        .to_owned();
+    if !codes.is_empty() {
+        let codes: Vec<String> = codes.iter().map(|c| c.as_ref().to_owned()).collect();
+        query.and_where(Expr::col((WordBook, word_book::Column::Code)).is_in(codes));
+    }
+    SimpleExpr::SubQuery(None, Box::new(query.into_sub_query_statement()))
+}
+
+// 把单词加入词书：(master_word id, 在词书里的顺序)，已经在词书里的不变
+pub async fn add_to_book<C: ConnectionTrait>(
+    db: &C,
+    book_id: i32,
+    words: &[(i32, i32)],
+) -> Result<(), DbErr> {
+    for chunk in words.chunks(300) {
+        let rows =
+            chunk.iter().map(
+                |&(master_word_id, sort_order)| word_book_membership::ActiveModel {
+                    word_book_id: Set(book_id),
+                    master_word_id: Set(master_word_id),
+                    sort_order: Set(sort_order),
+                },
+            );
+        WordBookMembership::insert_many(rows)
+            .on_conflict(
+                OnConflict::columns([
+                    word_book_membership::Column::WordBookId,
+                    word_book_membership::Column::MasterWordId,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .do_nothing()
+            .exec(db)
+            .await?;
+    }
+    Ok(())
+}
+
+// 每个单词属于哪些词书，按 master_word id 分组
+pub async fn books_of<C: ConnectionTrait>(
+    db: &C,
+    master_ids: &[i32],
+) -> Result<HashMap<i32, Vec<Source>>, DbErr> {
+    let mut books: HashMap<i32, Vec<Source>> = HashMap::new();
+    for chunk in master_ids.chunks(500) {
+        let rows: Vec<(i32, String)> = WordBookMembership::find()
+            .join(
+                JoinType::InnerJoin,
+                word_book_membership::Relation::WordBook.def(),
+            )
+            .filter(word_book_membership::Column::MasterWordId.is_in(chunk.to_vec()))
+            .order_by_asc(word_book::Column::Id)
+            .select_only()
+            .column(word_book_membership::Column::MasterWordId)
+            .column(word_book::Column::Code)
+            .into_tuple()
+            .all(db)
+            .await?;
+        for (master_id, code) in rows {
+            if let Ok(source) = code.parse::<Source>() {
+                books.entry(master_id).or_default().push(source);
+            }
+        }
+    }
+    Ok(books)
+}
+
+// 单词所属的第一本词书，用来选按词书拟合的记忆参数
+pub async fn primary_book(
+    db: &DatabaseConnection,
+    master_id: i32,
+) -> Result<Option<String>, DbErr> {
+    WordBook::find()
+        .join(
+            JoinType::InnerJoin,
+            word_book::Relation::WordBookMembership.def(),
+        )
+        .filter(word_book_membership::Column::MasterWordId.eq(master_id))
+        .order_by_asc(word_book::Column::Id)
+        .select_only()
+        .column(word_book::Column::Code)
+        .into_tuple::<String>()
+        .one(db)
+        .await
+}
+
+//列出所有内置词书和安装情况
+pub async fn list_books(
+    db: &DatabaseConnection,
+    registry: &BookRegistry,
+) -> Result<Vec<BookInfo>, DbErr> {
+    let mut books = Vec::new();
+    for book in books::BOOKS.iter() {
+        let word_count = WordBook::find()
+            .filter(word_book::Column::Code.eq(book.source.as_ref()))
+            .one(db)
+            .await?
+            .and_then(|b| b.word_count);
+        let installed_count = MasterWord::find()
+            .filter(in_books(&[&book.source]))
+            .count(db)
+            .await?;
+
+        books.push(BookInfo {
+            source: book.source.clone(),
+            name: book.name.to_owned(),
+            available: registry.is_available(book),
+            word_count,
+            installed_count,
+        });
+    }
+    Ok(books)
+}
+
+//安装词书：把词书里的单词加入词库，已有的单词只加入词书
+pub async fn install_book(
+    db: &DatabaseConnection,
+    registry: &BookRegistry,
+    source: Source,
+) -> Result<ImportReport, DbErr> {
+    let words = registry.load(&source)?;
+    let report = import_service::insert_words(db, &words, &source).await?;
+    set_word_count(db, &source, words.len()).await?;
+    master_word_service::fill_frequency_ranks(db, registry).await?;
+    dictionary_service::fill_irregular_inflections(db).await?;
+    Ok(report)
+}
+
+//卸载词书：单词移出词书，只属于这本词书且还没开始学的单词从词库删除
+pub async fn uninstall_book(db: &DatabaseConnection, source: Source) -> Result<u64, DbErr> {
+    let id = book_id(db, &source).await?;
+    let learning = Query::select()
+        .column(user_word::Column::MasterWordId)
+        .from(user_word::Entity)
+        .to_owned();
+    let in_other_books = Query::select()
+        .column(word_book_membership::Column::MasterWordId)
+        .from(WordBookMembership)
+        .and_where(word_book_membership::Column::WordBookId.ne(id))
+        .to_owned();
+
+    let result = MasterWord::delete_many()
+        .filter(in_books(&[&source]))
+        .filter(master_word::Column::Id.not_in_subquery(learning))
+        .filter(master_word::Column::Id.not_in_subquery(in_other_books))
+        .exec(db)
+        .await?;
+    WordBookMembership::delete_many()
+        .filter(word_book_membership::Column::WordBookId.eq(id))
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected)
+}
@@ -0,0 +1,540 @@
+use sea_orm::{
+    sea_query::OnConflict, ActiveValue::Set, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait, TryInsertResult,
+};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    io::BufRead,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use crate::{
+    db,
+    entities::{
+        master_word,
+        prelude::{MasterWord, WordInflection, WordSense},
+        word_inflection, word_sense,
+    },
+    hanzi, morphology,
+    services::{import_service, master_word_service},
+};
+
+// ECDICT exchange 字段里的变形代码；0 和 1 是反过来指向原形的，不需要
+const EXCHANGE_KINDS: [(&str, &str); 7] = [
+    ("p", "past"),
+    ("d", "past_participle"),
+    ("i", "present_participle"),
+    ("3", "third_person"),
+    ("s", "plural"),
+    ("r", "comparative"),
+    ("t", "superlative"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DictionaryProgress {
+    pub job_id: u32,
+    pub read_bytes: u64,
+    pub total_bytes: u64,
+    pub matched: u32, // 目前匹配到词库里的单词数
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DictionaryReport {
+    pub matched: u32,             // 词典里找到的词库单词
+    pub pronunciations: u32,      // 补上音标的单词
+    pub senses_added: u32,        // 新增的释义
+    pub inflections_added: usize, // 新增的变形
+}
+
+// 词典 CSV 的表头，按列名找字段，不认识的列忽略
+struct Columns {
+    word: usize,
+    phonetic: Option<usize>,
+    us: Option<usize>,
+    uk: Option<usize>,
+    translation: Option<usize>,
+    exchange: Option<usize>,
+}
+
+impl Columns {
+    fn from_header(header: &[String]) -> Result<Self, DbErr> {
+        let find = |names: &[&str]| {
+            header
+                .iter()
+                .position(|h| names.contains(&h.to_lowercase().as_str()))
+        };
+        Ok(Self {
+            word: find(&["word"]).ok_or_else(|| DbErr::Custom("词典缺少 word 列".to_owned()))?,
+            phonetic: find(&["phonetic"]),
+            us: find(&["phonetic_us", "us"]),
+            uk: find(&["phonetic_uk", "uk"]),
+            translation: find(&["translation"]),
+            exchange: find(&["exchange"]),
+        })
+    }
+}
+
+fn field(fields: &[String], index: Option<usize>) -> Option<&str> {
+    index
+        .and_then(|i| fields.get(i))
+        .map(|f| f.trim())
+        .filter(|f| !f.is_empty())
+}
+
+// "p:went/d:gone/i:going" -> [("went", "past"), ...]
+fn parse_exchange(exchange: &str) -> Vec<(String, &'static str)> {
+    exchange
+        .split('/')
+        .filter_map(|item| item.split_once(':'))
+        .filter_map(|(code, form)| {
+            let (_, kind) = EXCHANGE_KINDS.iter().find(|(c, _)| *c == code)?;
+            let form = form.trim().to_lowercase();
+            (!form.is_empty()).then_some((form, *kind))
+        })
+        .collect()
+}
+
+// 词典里的一行，只留下用得到的列
+struct DictEntry {
+    word: String,
+    phonetic: Option<String>,
+    us: Option<String>,
+    uk: Option<String>,
+    translation: Option<String>,
+    exchange: Option<String>,
+}
+
+// 按块读词典：读文件是阻塞的，每块放到阻塞线程里读
+struct DictReader<R> {
+    reader: R,
+    columns: Option<Columns>,
+    read_bytes: u64,
+    done: bool,
+}
+
+impl<R: BufRead> DictReader<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            columns: None,
+            read_bytes: 0,
+            done: false,
+        }
+    }
+
+    // 最多读 max_lines 行，读到文件末尾时 done 为 true
+    fn next_chunk(&mut self, max_lines: usize) -> Result<Vec<DictEntry>, DbErr> {
+        let mut entries = Vec::new();
+        let mut buf = Vec::new();
+        for _ in 0..max_lines {
+            buf.clear();
+            let n = self
+                .reader
+                .read_until(b'\n', &mut buf)
+                .map_err(|e| DbErr::Custom(format!("读取词典失败: {}", e)))?;
+            if n == 0 {
+                self.done = true;
+                break;
+            }
+            self.read_bytes += n as u64;
+            let line = String::from_utf8_lossy(&buf);
+            let line = line.trim_start_matches('\u{feff}').trim_end();
+            if line.is_empty() {
+                continue;
+            }
+            let fields = import_service::split_line(line, ',');
+
+            let Some(cols) = &self.columns else {
+                self.columns = Some(Columns::from_header(&fields)?);
+                continue;
+            };
+            let text = |index| field(&fields, index).map(str::to_owned);
+            let Some(word) = text(Some(cols.word)) else {
+                continue;
+            };
+            entries.push(DictEntry {
+                word,
+                phonetic: text(cols.phonetic),
+                us: text(cols.us),
+                uk: text(cols.uk),
+                translation: text(cols.translation),
+                exchange: text(cols.exchange),
+            });
+        }
+        Ok(entries)
+    }
+}
+
+// 每次读多少行、提交一次
+const CHUNK_LINES: usize = 5000;
+
+// 一块词条里要写回数据库的改动，攒起来批量写
+#[derive(Default)]
+struct ChunkChanges {
+    pronunciation: Vec<(i32, String)>,
+    pronunciation_us: Vec<(i32, String)>,
+    pronunciation_uk: Vec<(i32, String)>,
+    definition: Vec<(i32, String)>,
+    senses: Vec<word_sense::ActiveModel>,
+    inflections: Vec<word_inflection::ActiveModel>,
+}
+
+impl ChunkChanges {
+    async fn write<C: ConnectionTrait>(self, db: &C) -> Result<usize, DbErr> {
+        use master_word::Column;
+        db::update_column_by_id(db, Column::Id, Column::Pronunciation, self.pronunciation).await?;
+        db::update_column_by_id(
+            db,
+            Column::Id,
+            Column::PronunciationUs,
+            self.pronunciation_us,
+        )
+        .await?;
+        db::update_column_by_id(
+            db,
+            Column::Id,
+            Column::PronunciationUk,
+            self.pronunciation_uk,
+        )
+        .await?;
+        db::update_column_by_id(db, Column::Id, Column::Definition, self.definition).await?;
+        db::insert_details(db, self.senses, Vec::new()).await?;
+
+        insert_inflections(db, self.inflections).await
+    }
+}
+
+// 分批写入单词变形，已经有的不重复插入；返回新插入的条数
+async fn insert_inflections<C: ConnectionTrait>(
+    db: &C,
+    inflections: Vec<word_inflection::ActiveModel>,
+) -> Result<usize, DbErr> {
+    let mut inserted = 0;
+    for chunk in inflections.chunks(100) {
+        inserted += match WordInflection::insert_many(chunk.to_vec())
+            .on_conflict(
+                OnConflict::columns([
+                    word_inflection::Column::MasterWordId,
+                    word_inflection::Column::Kind,
+                    word_inflection::Column::Form,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .do_nothing()
+            .exec_without_returning(db)
+            .await?
+        {
+            TryInsertResult::Inserted(n) => n as usize,
+            _ => 0,
+        };
+    }
+    Ok(inserted)
+}
+
+// 内置的不规则变形写进 word_inflection 表，只写词库里有原形的；启动和导入单词后调用
+pub async fn fill_irregular_inflections<C: ConnectionTrait>(db: &C) -> Result<usize, DbErr> {
+    let lemmas: HashSet<&str> = morphology::IRREGULAR.iter().map(|(_, l, _)| *l).collect();
+    let ids: HashMap<String, i32> = MasterWord::find()
+        .filter(master_word_service::text_in_nocase(lemmas))
+        .select_only()
+        .column(master_word::Column::Text)
+        .column(master_word::Column::Id)
+        .into_tuple::<(String, i32)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|(text, id)| (text.to_lowercase(), id))
+        .collect();
+
+    let inflections = morphology::IRREGULAR
+        .iter()
+        .filter_map(|(form, lemma, kind)| {
+            ids.get(*lemma).map(|&id| word_inflection::ActiveModel {
+                master_word_id: Set(id),
+                form: Set(form.to_string()),
+                kind: Set(kind.to_string()),
+                ..Default::default()
+            })
+        })
+        .collect();
+    insert_inflections(db, inflections).await
+}
+
+// 一个词条对应的改动：只补空着的字段，不覆盖已有的内容
+fn collect_changes(
+    entry: DictEntry,
+    master: master_word::Model,
+    existing: Vec<word_sense::Model>,
+    changes: &mut ChunkChanges,
+    report: &mut DictionaryReport,
+) {
+    let master_id = master.id;
+
+    // 补音标
+    let mut changed = false;
+    if master.pronunciation_us.is_none() {
+        if let Some(us) = &entry.us {
+            changes.pronunciation_us.push((master_id, us.clone()));
+            changed = true;
+        }
+    }
+    if master.pronunciation_uk.is_none() {
+        if let Some(uk) = &entry.uk {
+            changes.pronunciation_uk.push((master_id, uk.clone()));
+            changed = true;
+        }
+    }
+    if master.pronunciation.is_none() {
+        if let Some(p) = entry.phonetic.or(entry.us).or(entry.uk) {
+            changes.pronunciation.push((master_id, p));
+            changed = true;
+        }
+    }
+    if changed {
+        report.pronunciations += 1;
+    }
+
+    // 补释义：词典里的释义用字面上的 \n 分行，已有的释义不重复添加
+    let mut known: HashSet<String> = existing.iter().map(|s| s.translation.clone()).collect();
+    let mut added = 0;
+    let mut definition = master.definition;
+    let translation = entry.translation.unwrap_or_default();
+    for line in translation
+        .split("\\n")
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+    {
+        let (pos, text) = import_service::split_pos(line);
+        if !known.insert(text.clone()) {
+            continue;
+        }
+        changes.senses.push(word_sense::ActiveModel {
+            master_word_id: Set(master_id),
+            pos: Set(pos),
+            pinyin: Set(Some(hanzi::plain_pinyin(&text))),
+            translation: Set(text),
+            sort_order: Set((existing.len() + added) as i32),
+            ..Default::default()
+        });
+        added += 1;
+        if !definition.is_empty() {
+            definition.push('\n');
+        }
+        definition.push_str(line);
+    }
+    if added > 0 {
+        report.senses_added += added as u32;
+        changes.definition.push((master_id, definition));
+    }
+
+    changes.inflections.extend(
+        parse_exchange(entry.exchange.as_deref().unwrap_or_default())
+            .into_iter()
+            .map(|(form, kind)| word_inflection::ActiveModel {
+                master_word_id: Set(master_id),
+                form: Set(form),
+                kind: Set(kind.to_owned()),
+                ..Default::default()
+            }),
+    );
+}
+
+// 补全任务的编号，前端用它对应进度和结果事件
+static NEXT_JOB_ID: AtomicU32 = AtomicU32::new(1);
+
+pub fn next_job_id() -> u32 {
+    NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+// dictionary-finished 事件的内容，report 和 error 只有一个不为空
+#[derive(Debug, Serialize, Clone)]
+pub struct DictionaryFinished {
+    pub job_id: u32,
+    pub report: Option<DictionaryReport>,
+    pub error: Option<String>,
+}
+
+impl DictionaryFinished {
+    pub fn new(job_id: u32, result: Result<DictionaryReport, DbErr>) -> Self {
+        match result {
+            Ok(report) => Self {
+                job_id,
+                report: Some(report),
+                error: None,
+            },
+            Err(e) => Self {
+                job_id,
+                report: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+//用 ECDICT 格式的 CSV 词典 (用户自己选的文件，不随安装包提供) 补全词库：音标、缺少的释义和单词变形
+//只补空着的字段，不覆盖已有的内容；每读 CHUNK_LINES 行提交一次并调用 on_progress，
+//中途出错时已经提交的部分保留，再跑一遍会接着补
+pub async fn enrich_from_csv<R: BufRead + Send + 'static>(
+    db: &DatabaseConnection,
+    job_id: u32,
+    reader: R,
+    total_bytes: u64,
+    mut on_progress: impl FnMut(DictionaryProgress),
+) -> Result<DictionaryReport, DbErr> {
+    let mut masters: HashMap<String, master_word::Model> = MasterWord::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|m| (m.text.to_lowercase(), m))
+        .collect();
+    let mut senses: HashMap<i32, Vec<word_sense::Model>> = HashMap::new();
+    for sense in WordSense::find()
+        .order_by_asc(word_sense::Column::SortOrder)
+        .all(db)
+        .await?
+    {
+        senses.entry(sense.master_word_id).or_default().push(sense);
+    }
+
+    let mut report = DictionaryReport::default();
+    let mut source = DictReader::new(reader);
+    while !source.done {
+        let (back, entries) = tauri::async_runtime::spawn_blocking(move || {
+            let entries = source.next_chunk(CHUNK_LINES);
+            (source, entries)
+        })
+        .await
+        .map_err(|e| DbErr::Custom(format!("读取词典失败: {}", e)))?;
+        source = back;
+
+        let mut changes = ChunkChanges::default();
+        for entry in entries? {
+            // 每个单词只用第一条词条，大小写不同的重复词条跳过
+            let Some(master) = masters.remove(&entry.word.to_lowercase()) else {
+                continue;
+            };
+            report.matched += 1;
+            let existing = senses.remove(&master.id).unwrap_or_default();
+            collect_changes(entry, master, existing, &mut changes, &mut report);
+        }
+
+        let txn = db.begin().await?;
+        report.inflections_added += changes.write(&txn).await?;
+        txn.commit().await?;
+
+        on_progress(DictionaryProgress {
+            job_id,
+            read_bytes: source.read_bytes,
+            total_bytes,
+            matched: report.matched,
+        });
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::source_enum::Source;
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::{ColumnTrait, Database};
+    use std::io::Cursor;
+
+    // ECDICT 的列顺序不固定，多出来的列 (definition、bnc) 要忽略
+    const FIXTURE: &str =
+        "\u{feff}word,phonetic,phonetic_us,phonetic_uk,definition,translation,bnc,exchange
+go,gəʊ,ɡoʊ,ɡəʊ,to move,\"v. 去\\nn. 尝试\",50,p:went/d:gone/i:going/3:goes/0:go
+Apple,ˈæpl,,,a fruit,n. 苹果,800,s:apples
+zebra,ˈzebrə,,,an animal,n. 斑马,9000,s:zebras
+";
+
+    async fn word(db: &DatabaseConnection, text: &str) -> master_word::Model {
+        MasterWord::find()
+            .filter(master_word::Column::Text.eq(text))
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn enrich_from_csv_fixture() {
+        tauri::async_runtime::block_on(async {
+            let db = Database::connect("sqlite::memory:").await.unwrap();
+            Migrator::up(&db, None).await.unwrap();
+            import_service::import_word_list(
+                &db,
+                "go,v. 去\napple,n. 苹果\n",
+                import_service::ImportFormat::Csv,
+                Source::Custom,
+            )
+            .await
+            .unwrap();
+
+            let mut progress = Vec::new();
+            let total = FIXTURE.len() as u64;
+            let report = enrich_from_csv(
+                &db,
+                7,
+                Cursor::new(FIXTURE.as_bytes().to_vec()),
+                total,
+                |p| progress.push(p),
+            )
+            .await
+            .unwrap();
+            assert_eq!(report.matched, 2);
+            assert_eq!(report.pronunciations, 2);
+            assert_eq!(report.senses_added, 1);
+            assert_eq!(report.inflections_added, 5);
+
+            // 音标：美式 / 英式分开存，默认显示 phonetic 列
+            let go = word(&db, "go").await;
+            assert_eq!(go.pronunciation.as_deref(), Some("gəʊ"));
+            assert_eq!(go.pronunciation_us.as_deref(), Some("ɡoʊ"));
+            assert_eq!(go.pronunciation_uk.as_deref(), Some("ɡəʊ"));
+            assert_eq!(go.definition, "v. 去\nn. 尝试");
+            let apple = word(&db, "apple").await;
+            assert_eq!(apple.pronunciation.as_deref(), Some("ˈæpl"));
+            assert_eq!(apple.pronunciation_us, None);
+
+            // exchange 里的变形能查回原形，0: 指向原形的不要
+            let went = master_word_service::lookup_word(&db, "went".to_owned())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(went.word.id, go.id);
+            assert_eq!(went.matched_kind.as_deref(), Some("past"));
+            let mut forms: Vec<String> = went.inflections.into_iter().map(|i| i.form).collect();
+            forms.sort();
+            assert_eq!(forms, ["goes", "going", "gone", "went"]);
+
+            // 一块就读完了，只有一次进度，读到文件末尾
+            assert_eq!(progress.len(), 1);
+            assert_eq!(progress[0].job_id, 7);
+            assert_eq!(progress[0].matched, 2);
+            assert_eq!(progress[0].read_bytes, total);
+            assert_eq!(progress[0].total_bytes, total);
+
+            // 再跑一遍什么都不会重复添加
+            let again = enrich_from_csv(
+                &db,
+                8,
+                Cursor::new(FIXTURE.as_bytes().to_vec()),
+                total,
+                |_| {},
+            )
+            .await
+            .unwrap();
+            assert_eq!(
+                (
+                    again.pronunciations,
+                    again.senses_added,
+                    again.inflections_added
+                ),
+                (0, 0, 0)
+            );
+        });
+    }
+}
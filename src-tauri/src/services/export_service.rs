@@ -0,0 +1,129 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entities::{
+        card_state::CardState, master_word, prelude::UserWord, source_enum::Source, user_word,
+    },
+    services::book_service,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    Csv,
+    AnkiText, // Anki "Basic" 笔记类型的文本导入格式
+}
+
+// 导出条件，不填的条件不限制
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ExportFilter {
+    pub states: Vec<CardState>,
+    pub min_difficulty: Option<f32>,     // 难度大于这个值 (1-10)
+    pub books: Vec<Source>,              // 属于其中任意一本词书
+    pub due_from: Option<DateTime<Utc>>, // 到期时间范围
+    pub due_to: Option<DateTime<Utc>>,
+}
+
+fn filter_condition(filter: &ExportFilter) -> Condition {
+    let mut condition = Condition::all();
+    if !filter.states.is_empty() {
+        let states: Vec<i16> = filter.states.iter().map(|s| s.as_i16()).collect();
+        condition = condition.add(user_word::Column::Status.is_in(states));
+    }
+    if let Some(d) = filter.min_difficulty {
+        condition = condition.add(user_word::Column::Difficulty.gt(d));
+    }
+    if !filter.books.is_empty() {
+        condition = condition.add(book_service::in_books(&filter.books));
+    }
+    if let Some(from) = filter.due_from {
+        condition = condition.add(user_word::Column::Due.gte(from));
+    }
+    if let Some(to) = filter.due_to {
+        condition = condition.add(user_word::Column::Due.lte(to));
+    }
+    condition
+}
+
+// 列的顺序和 import_word_list 一致 (多个词性用 ; 隔开)，导出的文件可以直接再导入
+// 含逗号、引号或换行的字段要用引号包起来
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+// Anki 文本导入用 tab 分隔，字段按 HTML 解析：先转义 & < >，再把换行换成 <br>
+fn anki_field(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\t', " ")
+        .replace('\n', "<br>")
+}
+
+//按条件导出单词，返回文件内容和导出的单词数
+pub async fn export_words(
+    db: &DatabaseConnection,
+    filter: &ExportFilter,
+    format: ExportFormat,
+) -> Result<(String, usize), DbErr> {
+    let rows = UserWord::find()
+        .find_also_related(master_word::Entity)
+        .filter(filter_condition(filter))
+        .order_by_desc(user_word::Column::Difficulty)
+        .order_by_asc(master_word::Column::Text)
+        .all(db)
+        .await?;
+    let rows: Vec<(user_word::Model, master_word::Model)> = rows
+        .into_iter()
+        .filter_map(|(user, master)| Some((user, master?)))
+        .collect();
+
+    let mut out = String::new();
+    match format {
+        ExportFormat::Csv => {
+            out.push_str(
+                "word,definition,pronunciation,state,difficulty,stability,due,last_review\n",
+            );
+            for (user, master) in &rows {
+                let state = CardState::from_i16(user.status).unwrap_or(CardState::New);
+                let fields = [
+                    master.text.clone(),
+                    master.definition.replace('\n', "; "),
+                    master.pronunciation.clone().unwrap_or_default(),
+                    format!("{:?}", state),
+                    format!("{:.2}", user.difficulty),
+                    format!("{:.2}", user.stability),
+                    user.due.to_rfc3339(),
+                    user.last_review.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                ];
+                let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+                out.push_str(&line.join(","));
+                out.push('\n');
+            }
+        }
+        ExportFormat::AnkiText => {
+            out.push_str("#separator:tab\n#html:true\n#notetype:Basic\n");
+            for (_, master) in &rows {
+                let back = match &master.pronunciation {
+                    Some(p) => format!("{}\n{}", p, master.definition),
+                    None => master.definition.clone(),
+                };
+                out.push_str(&format!(
+                    "{}\t{}\n",
+                    anki_field(&master.text),
+                    anki_field(&back)
+                ));
+            }
+        }
+    }
+
+    Ok((out, rows.len()))
+}
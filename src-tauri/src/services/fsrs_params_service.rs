@@ -0,0 +1,150 @@
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    JoinType, QueryFilter, QueryOrder, QuerySelect, RelationTrait,
+};
+use serde::Serialize;
+
+use crate::{
+    algorithm::{self, Rating},
+    entities::{fsrs_params, prelude::FsrsParams, prelude::ReviewLog, review_log, user_word},
+    optimizer::{self, ReviewSequence},
+    services::book_service,
+};
+
+#[derive(Serialize)]
+pub struct OptimizeReport {
+    pub deck: Option<String>,
+    pub params: Vec<f32>,
+    pub loss_before: f32,
+    pub loss_after: f32,
+    pub review_count: u64,
+}
+
+async fn find_row(
+    db: &DatabaseConnection,
+    deck: Option<&str>,
+) -> Result<Option<fsrs_params::Model>, DbErr> {
+    let query = match deck {
+        Some(d) => FsrsParams::find().filter(fsrs_params::Column::Deck.eq(d)),
+        None => FsrsParams::find().filter(fsrs_params::Column::Deck.is_null()),
+    };
+    query.one(db).await
+}
+
+fn parse_params(raw: &str) -> Option<[f32; 19]> {
+    let v: Vec<f32> = serde_json::from_str(raw).ok()?;
+    v.try_into().ok()
+}
+
+//读取参数：优先词书自己的参数，其次全局参数，最后是默认参数
+pub async fn load_params(db: &DatabaseConnection, deck: Option<&str>) -> Result<[f32; 19], DbErr> {
+    if deck.is_some() {
+        if let Some(p) = find_row(db, deck)
+            .await?
+            .and_then(|r| parse_params(&r.params))
+        {
+            return Ok(p);
+        }
+    }
+    Ok(find_row(db, None)
+        .await?
+        .and_then(|r| parse_params(&r.params))
+        .unwrap_or(algorithm::DEFAULT_PARAMS))
+}
+
+// 把复习日志按单词分组，整理成优化器需要的序列
+async fn load_sequences(
+    db: &DatabaseConnection,
+    deck: Option<&str>,
+) -> Result<Vec<ReviewSequence>, DbErr> {
+    let mut query = ReviewLog::find()
+        .join(JoinType::InnerJoin, review_log::Relation::UserWord.def())
+        .join(JoinType::InnerJoin, user_word::Relation::MasterWord.def());
+
+    if let Some(d) = deck {
+        query = query.filter(book_service::in_books(&[d]));
+    }
+
+    let logs = query
+        .order_by_asc(review_log::Column::UserWordId)
+        .order_by_asc(review_log::Column::ReviewedAt)
+        .order_by_asc(review_log::Column::Id)
+        .all(db)
+        .await?;
+
+    let mut sequences: Vec<ReviewSequence> = Vec::new();
+    let mut current_id = None;
+    for log in logs {
+        let Some(rating) = Rating::from_i32(log.rating as i32) else {
+            continue;
+        };
+        if current_id != Some(log.user_word_id) {
+            current_id = Some(log.user_word_id);
+            sequences.push(Vec::new());
+        }
+        if let Some(seq) = sequences.last_mut() {
+            seq.push((rating, log.elapsed_days));
+        }
+    }
+
+    Ok(sequences)
+}
+
+//用复习记录拟合参数，只返回结果，不保存
+pub async fn optimize(
+    db: &DatabaseConnection,
+    deck: Option<String>,
+) -> Result<OptimizeReport, DbErr> {
+    let current = load_params(db, deck.as_deref()).await?;
+    let sequences = load_sequences(db, deck.as_deref()).await?;
+    // 优化要重放几百遍复习记录，放到阻塞线程里跑，不占用命令线程
+    let result =
+        tauri::async_runtime::spawn_blocking(move || optimizer::optimize(&current, &sequences))
+            .await
+            .map_err(|e| DbErr::Custom(format!("参数优化失败: {}", e)))?;
+
+    Ok(OptimizeReport {
+        deck,
+        params: result.params.to_vec(),
+        loss_before: result.loss_before,
+        loss_after: result.loss_after,
+        review_count: result.predictions as u64,
+    })
+}
+
+//保存参数，之后的复习都会用这组参数排期
+pub async fn apply_params(
+    db: &DatabaseConnection,
+    deck: Option<String>,
+    params: Vec<f32>,
+) -> Result<(), DbErr> {
+    let mut params: [f32; 19] = params
+        .try_into()
+        .map_err(|_| DbErr::Custom("FSRS 参数必须是 19 个".to_owned()))?;
+    if params.iter().any(|p| !p.is_finite()) {
+        return Err(DbErr::Custom("FSRS 参数不能是 NaN 或无穷大".to_owned()));
+    }
+    // 超出取值范围的参数会让排期失控，按优化器的范围截断
+    optimizer::clamp_params(&mut params);
+
+    let sequences = load_sequences(db, deck.as_deref()).await?;
+    let (loss, count) = optimizer::log_loss(&params, &sequences);
+    let params_json =
+        serde_json::to_string(&params.to_vec()).map_err(|e| DbErr::Custom(e.to_string()))?;
+
+    let mut active_model: fsrs_params::ActiveModel = match find_row(db, deck.as_deref()).await? {
+        Some(row) => row.into(),
+        None => fsrs_params::ActiveModel {
+            deck: Set(deck),
+            ..Default::default()
+        },
+    };
+    active_model.params = Set(params_json);
+    active_model.log_loss = Set((count > 0).then_some(loss));
+    active_model.review_count = Set(count as i32);
+    active_model.updated_at = Set(Utc::now().into());
+    active_model.save(db).await?;
+
+    Ok(())
+}
@@ -0,0 +1,400 @@
+use chrono::Utc;
+use sea_orm::{
+    ActiveValue::Set, DatabaseConnection, DbErr, EntityTrait, QuerySelect, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    db::{self, JsonTranslation, JsonWord},
+    entities::{master_word, prelude::MasterWord, source_enum::Source},
+    services::book_service,
+};
+
+// 支持的词表格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportFormat {
+    Csv,
+    Tsv,
+    Json,
+}
+
+impl ImportFormat {
+    // 按扩展名猜格式，猜不出来返回 None；.txt 可能是任何一种，要看内容
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "csv" => Some(ImportFormat::Csv),
+            "tsv" => Some(ImportFormat::Tsv),
+            "json" => Some(ImportFormat::Json),
+            _ => None,
+        }
+    }
+
+    // 按内容猜格式：[ 开头是 JSON，第一行有制表符是 TSV，有逗号是 CSV
+    pub fn from_content(content: &str) -> Option<Self> {
+        let content = content.trim_start_matches('\u{feff}').trim_start();
+        if content.starts_with('[') {
+            return Some(ImportFormat::Json);
+        }
+        let first_line = content.lines().next()?;
+        if first_line.contains('\t') {
+            Some(ImportFormat::Tsv)
+        } else if first_line.contains(',') {
+            Some(ImportFormat::Csv)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct InvalidRow {
+    pub line: usize, // 第几行 (JSON 为第几个元素)，从 1 开始
+    pub content: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ImportReport {
+    pub inserted: u32,
+    pub skipped: Vec<String>, // 词库里已有或文件里重复的单词
+    pub invalid: Vec<InvalidRow>,
+}
+
+// 第一列是这些值时当作表头跳过
+const HEADER_NAMES: [&str; 3] = ["word", "text", "单词"];
+
+// CSV / TSV 里的一条记录，引号里的字段可以换行，所以一条记录可能跨好几行
+pub(crate) struct Record<'a> {
+    pub line: usize,  // 记录从第几行开始，从 1 开始
+    pub raw: &'a str, // 原始内容，报错时显示
+    pub fields: Vec<String>,
+}
+
+fn new_record(
+    content: &str,
+    start: usize,
+    end: usize,
+    line: usize,
+    fields: Vec<String>,
+) -> Record<'_> {
+    Record {
+        line,
+        raw: content[start..end].trim_end_matches('\r'),
+        fields: fields.into_iter().map(|f| f.trim().to_owned()).collect(),
+    }
+}
+
+// 按分隔符拆分记录，支持双引号包起来的字段 (可以包含分隔符和换行) 和 "" 转义
+pub(crate) fn split_records(content: &str, delimiter: char) -> Vec<Record<'_>> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let (mut start, mut start_line) = (0, 1);
+    let mut chars = content.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek().map(|(_, c)| *c) == Some('"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            '\n' => {
+                line += 1;
+                if in_quotes {
+                    field.push('\n');
+                    continue;
+                }
+                fields.push(std::mem::take(&mut field));
+                let done = std::mem::take(&mut fields);
+                records.push(new_record(content, start, i, start_line, done));
+                (start, start_line) = (i + 1, line);
+            }
+            // \r\n 换行只保留 \n
+            '\r' if !in_quotes || chars.peek().map(|(_, c)| *c) == Some('\n') => {}
+            c if c == delimiter && !in_quotes => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if start < content.len() {
+        fields.push(field);
+        records.push(new_record(
+            content,
+            start,
+            content.len(),
+            start_line,
+            fields,
+        ));
+    }
+    records
+}
+
+// 按分隔符拆一行，规则和 split_records 一样
+pub(crate) fn split_line(line: &str, delimiter: char) -> Vec<String> {
+    split_records(line, delimiter)
+        .into_iter()
+        .next()
+        .map(|r| r.fields)
+        .unwrap_or_else(|| vec![String::new()])
+}
+
+// "n. 苹果" -> (Some("n"), "苹果")
+pub(crate) fn split_pos(definition: &str) -> (Option<String>, String) {
+    if let Some((pos, rest)) = definition.split_once(". ") {
+        if !pos.is_empty() && pos.chars().all(|c| c.is_ascii_alphabetic() || c == '&') {
+            return (Some(pos.to_owned()), rest.trim().to_owned());
+        }
+    }
+    (None, definition.to_owned())
+}
+
+// CSV / TSV：单词, 释义[, 音标]，释义可以用 ; 分隔多个词性
+fn parse_delimited(content: &str, delimiter: char) -> (Vec<JsonWord>, Vec<InvalidRow>) {
+    let mut words = Vec::new();
+    let mut invalid = Vec::new();
+
+    for record in split_records(content, delimiter) {
+        if record.raw.trim().is_empty() {
+            continue;
+        }
+        let fields = record.fields;
+        if record.line == 1 && HEADER_NAMES.contains(&fields[0].to_lowercase().as_str()) {
+            continue;
+        }
+
+        let word = fields[0].clone();
+        let translations: Vec<JsonTranslation> = fields
+            .get(1)
+            .map(String::as_str)
+            .unwrap_or_default()
+            .split([';', '；'])
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                let (word_type, translation) = split_pos(s);
+                JsonTranslation {
+                    translation,
+                    word_type,
+                }
+            })
+            .collect();
+        if word.is_empty() || translations.is_empty() {
+            invalid.push(InvalidRow {
+                line: record.line,
+                content: record.raw.to_owned(),
+                reason: "缺少单词或释义".to_owned(),
+            });
+            continue;
+        }
+
+        words.push(JsonWord {
+            word,
+            translations,
+            phrases: None,
+            pronunciation: fields.get(2).filter(|p| !p.is_empty()).cloned(),
+        });
+    }
+
+    (words, invalid)
+}
+
+// JSON：和内置词库一样的 JsonWord 数组，单个元素格式不对只算无效行
+fn parse_json(content: &str) -> Result<(Vec<JsonWord>, Vec<InvalidRow>), DbErr> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(content)
+        .map_err(|e| DbErr::Custom(format!("JSON 格式错误: {}", e)))?;
+
+    let mut words = Vec::new();
+    let mut invalid = Vec::new();
+    for (i, value) in values.into_iter().enumerate() {
+        let content = value.to_string();
+        match serde_json::from_value::<JsonWord>(value) {
+            Ok(mut w) if !w.word.trim().is_empty() && !w.translations.is_empty() => {
+                w.word = w.word.trim().to_owned();
+                words.push(w);
+            }
+            Ok(_) => invalid.push(InvalidRow {
+                line: i + 1,
+                content,
+                reason: "缺少单词或释义".to_owned(),
+            }),
+            Err(e) => invalid.push(InvalidRow {
+                line: i + 1,
+                content,
+                reason: e.to_string(),
+            }),
+        }
+    }
+    Ok((words, invalid))
+}
+
+pub(crate) fn new_master_word(word: &JsonWord) -> master_word::ActiveModel {
+    master_word::ActiveModel {
+        text: Set(word.word.clone()),
+        definition: Set(word.definition()),
+        pronunciation: Set(word.pronunciation.clone()),
+        audio_url: Set(None),
+        created_at: Set(Utc::now().into()),
+        ..Default::default()
+    }
+}
+
+// 词库里已有的单词 (小写) -> id，用来去重
+pub(crate) async fn existing_texts(db: &DatabaseConnection) -> Result<HashMap<String, i32>, DbErr> {
+    Ok(MasterWord::find()
+        .select_only()
+        .column(master_word::Column::Text)
+        .column(master_word::Column::Id)
+        .into_tuple::<(String, i32)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|(text, id)| (text.to_lowercase(), id))
+        .collect())
+}
+
+// 批量写入单词：按单词去重 (不区分大小写)，新词和已有的词都加入 source 对应的词书
+pub(crate) async fn insert_words(
+    db: &DatabaseConnection,
+    words: &[JsonWord],
+    source: &Source,
+) -> Result<ImportReport, DbErr> {
+    let mut seen = existing_texts(db).await?;
+    let mut report = ImportReport::default();
+    let mut senses = Vec::new();
+    let mut phrases = Vec::new();
+    let mut members = Vec::new();
+
+    // 需要每个单词的 ID 来关联释义和短语，所以逐个插入，整体放在一个事务里
+    let txn = db.begin().await?;
+    let book_id = book_service::book_id(&txn, source).await?;
+    for (i, word) in words.iter().enumerate() {
+        let key = word.word.to_lowercase();
+        if let Some(&id) = seen.get(&key) {
+            report.skipped.push(word.word.clone());
+            members.push((id, i as i32));
+            continue;
+        }
+
+        let master_id = MasterWord::insert(new_master_word(word))
+            .exec(&txn)
+            .await?
+            .last_insert_id;
+        seen.insert(key, master_id);
+
+        let (s, p) = db::senses_and_phrases(word, master_id);
+        senses.extend(s);
+        phrases.extend(p);
+        members.push((master_id, i as i32));
+        report.inserted += 1;
+    }
+
+    db::insert_details(&txn, senses, phrases).await?;
+    book_service::add_to_book(&txn, book_id, &members).await?;
+    txn.commit().await?;
+
+    Ok(report)
+}
+
+//导入用户自己的词表
+pub async fn import_word_list(
+    db: &DatabaseConnection,
+    content: &str,
+    format: ImportFormat,
+    source: Source,
+) -> Result<ImportReport, DbErr> {
+    let content = content.trim_start_matches('\u{feff}');
+    let (words, invalid) = match format {
+        ImportFormat::Csv => parse_delimited(content, ','),
+        ImportFormat::Tsv => parse_delimited(content, '\t'),
+        ImportFormat::Json => parse_json(content)?,
+    };
+
+    let mut report = insert_words(db, &words, &source).await?;
+    report.invalid = invalid;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_line_handles_quotes() {
+        assert_eq!(split_line("apple, n. 苹果 ", ','), ["apple", "n. 苹果"]);
+        assert_eq!(
+            split_line(r#"apple,"n. 苹果, 苹果树",/ˈæpl/"#, ','),
+            ["apple", "n. 苹果, 苹果树", "/ˈæpl/"]
+        );
+        assert_eq!(
+            split_line(r#""say ""hi""",v. 打招呼"#, ','),
+            [r#"say "hi""#, "v. 打招呼"]
+        );
+        assert_eq!(split_line("a\tb\t", '\t'), ["a", "b", ""]);
+        assert_eq!(split_line("", ','), [""]);
+    }
+
+    #[test]
+    fn quoted_fields_can_span_lines() {
+        let content = "word,definition\r\napple,\"n. 苹果\r\nn. 苹果树\"\r\nbanana,n. 香蕉\n";
+        let records = split_records(content, ',');
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1].line, 2);
+        assert_eq!(records[1].fields, ["apple", "n. 苹果\nn. 苹果树"]);
+        assert_eq!(records[1].raw, "apple,\"n. 苹果\r\nn. 苹果树\"");
+        assert_eq!(records[2].line, 4);
+        assert_eq!(records[2].fields, ["banana", "n. 香蕉"]);
+    }
+
+    #[test]
+    fn parse_delimited_reports_invalid_rows_by_line() {
+        let content =
+            "word,definition\napple,\"n. 苹果;\nv. 摘\"\n,缺单词\nbanana,n. 香蕉,/bəˈnɑːnə/";
+        let (words, invalid) = parse_delimited(content, ',');
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].translations.len(), 2);
+        assert_eq!(words[0].translations[1].word_type.as_deref(), Some("v"));
+        assert_eq!(words[1].pronunciation.as_deref(), Some("/bəˈnɑːnə/"));
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].line, 4);
+    }
+
+    #[test]
+    fn split_pos_only_takes_short_ascii_prefix() {
+        assert_eq!(
+            split_pos("n. 苹果"),
+            (Some("n".to_owned()), "苹果".to_owned())
+        );
+        assert_eq!(split_pos("vt.& vi. 跑"), (None, "vt.& vi. 跑".to_owned()));
+        assert_eq!(split_pos("苹果. 水果"), (None, "苹果. 水果".to_owned()));
+    }
+
+    #[test]
+    fn format_detection() {
+        assert_eq!(
+            ImportFormat::from_path(Path::new("list.CSV")),
+            Some(ImportFormat::Csv)
+        );
+        assert_eq!(ImportFormat::from_path(Path::new("list.txt")), None);
+        assert_eq!(
+            ImportFormat::from_content("apple\tn. 苹果"),
+            Some(ImportFormat::Tsv)
+        );
+        assert_eq!(
+            ImportFormat::from_content("\u{feff}apple,n. 苹果"),
+            Some(ImportFormat::Csv)
+        );
+        assert_eq!(
+            ImportFormat::from_content(" [{\"word\": \"apple\"}]"),
+            Some(ImportFormat::Json)
+        );
+        assert_eq!(ImportFormat::from_content("apple"), None);
+    }
+}
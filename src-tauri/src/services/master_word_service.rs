@@ -1,66 +1,945 @@
 use sea_orm::{
-    ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
-    QuerySelect,
+    sea_query::{
+        Alias, Expr, JoinType, LikeExpr, NullOrdering, Query, SelectStatement, SimpleExpr,
+    },
+    ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, Order,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait, Select, SelectTwo,
+    TransactionTrait, Value,
 };
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
 
 use crate::{
+    books::{BookRegistry, FrequencyList},
     db,
-    entities::{master_word, prelude::MasterWord},
+    entities::{
+        card_state::CardState,
+        master_word,
+        prelude::{MasterWord, UserWord, WordInflection, WordPhrase, WordSense},
+        source_enum::Source,
+        user_word, word_inflection, word_phrase, word_sense,
+    },
+    hanzi,
+    models::{WordDetails, WordEntry},
+    morphology,
+    services::{book_service, settings_service},
 };
 
+// 单词列表的排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WordSort {
+    #[default]
+    Alphabetical,
+    Frequency, // 常用的在前，没有词频的排最后
+}
+
+// 只看某本词书里的单词，不填就是整个词库
+fn book_condition(book: Option<Source>) -> Condition {
+    let mut condition = Condition::all();
+    if let Some(b) = book {
+        condition = condition.add(book_service::in_books(&[b]));
+    }
+    condition
+}
+
+// 只看词频排名在前 max_rank 的单词
+fn rank_condition(max_rank: Option<i32>) -> Condition {
+    let mut condition = Condition::all();
+    if let Some(r) = max_rank {
+        condition = condition.add(master_word::Column::FrequencyRank.lte(r));
+    }
+    condition
+}
+
+// 只看学习状态在 states 里的单词，为空不限制
+fn state_condition(states: &[CardState]) -> Condition {
+    let mut condition = Condition::all();
+    if !states.is_empty() {
+        let states: Vec<i16> = states.iter().map(|s| s.as_i16()).collect();
+        condition = condition.add(
+            master_word::Column::Id.in_subquery(
+                Query::select()
+                    .column(user_word::Column::MasterWordId)
+                    .from(user_word::Entity)
+                    .and_where(user_word::Column::Status.is_in(states))
+                    .to_owned(),
+            ),
+        );
+    }
+    condition
+}
+
+pub(crate) fn order_by_frequency(query: Select<MasterWord>) -> Select<MasterWord> {
+    query.order_by_with_nulls(
+        master_word::Column::FrequencyRank,
+        Order::Asc,
+        NullOrdering::Last,
+    )
+}
+
+fn sorted(query: Select<MasterWord>, sort: WordSort) -> Select<MasterWord> {
+    match sort {
+        WordSort::Alphabetical => query.order_by_asc(master_word::Column::Text),
+        WordSort::Frequency => order_by_frequency(query).order_by_asc(master_word::Column::Text),
+    }
+}
+
+// 单词和它的学习进度，master_word 和 user_word 一对一 (user_word.master_word_id 有唯一索引)
+fn with_progress(query: Select<MasterWord>) -> SelectTwo<MasterWord, UserWord> {
+    query.find_also_related(UserWord)
+}
+
+// 带上每个单词所属的词书
+async fn into_entries(
+    db: &DatabaseConnection,
+    rows: Vec<(master_word::Model, Option<user_word::Model>)>,
+) -> Result<Vec<WordEntry>, DbErr> {
+    let ids: Vec<i32> = rows.iter().map(|(m, _)| m.id).collect();
+    let mut books = book_service::books_of(db, &ids).await?;
+    Ok(rows
+        .into_iter()
+        .map(|(master, user)| {
+            let word_books = books.remove(&master.id).unwrap_or_default();
+            WordEntry::from_query_result(master, user, word_books)
+        })
+        .collect())
+}
+
+//一次性返回所有单词，book 不传时是整个词库
 pub async fn get_all_master_words(
     db: &DatabaseConnection,
-) -> Result<Vec<master_word::Model>, DbErr> {
-    Ok(MasterWord::find().all(db).await?)
+    book: Option<Source>,
+) -> Result<Vec<WordEntry>, DbErr> {
+    let rows = with_progress(MasterWord::find().filter(book_condition(book)))
+        .all(db)
+        .await?;
+    into_entries(db, rows).await
 }
 
-//首字母返回
+//首字母返回，states 不为空时只返回在学且状态在其中的单词
 pub async fn get_master_word_by_first_letter(
     db: &DatabaseConnection,
     letter: Option<String>,
-) -> Result<Vec<master_word::Model>, DbErr> {
-    let mut query = MasterWord::find();
+    book: Option<Source>,
+    states: Vec<CardState>,
+) -> Result<Vec<WordEntry>, DbErr> {
+    let mut query = MasterWord::find()
+        .filter(book_condition(book))
+        .filter(state_condition(&states));
 
     if let Some(l) = letter {
-        let pattern = format!("{}%", l);
-        query = query.filter(master_word::Column::Text.like(pattern))
+        query = query.filter(starts_with(&l));
     }
 
-    query
+    let rows = with_progress(query)
         // 【解决乱序问题】强制按单词拼写排序
         .order_by_asc(master_word::Column::Text)
         // 【解决卡顿问题】如果是全部加载，这里可以限制数量，比如 .limit(50)
         // 但既然我们按字母分了，通常一个字母下的单词量不会特别大，直接返回即可
         .all(db)
-        .await
+        .await?;
+    into_entries(db, rows).await
+}
+
+// 单词列表的筛选条件，不填的条件不限制
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct WordListFilter {
+    pub letter: Option<String>, // 首字母
+    pub book: Option<Source>,
+    pub learned: Option<bool>,       // true 只看在学的，false 只看没学的
+    pub states: Vec<CardState>,      // 只看这些学习状态的单词
+    pub min_difficulty: Option<f32>, // 难度范围 (1-10)，只有在学的单词有难度
+    pub max_difficulty: Option<f32>,
+    pub max_rank: Option<i32>, // 只看词频排名在前 max_rank 的单词
+    pub sort: WordSort,
+}
+
+// 一页单词，next_cursor 传回来取下一页，为空说明到底了
+#[derive(Debug, Serialize)]
+pub struct WordPage {
+    pub items: Vec<WordEntry>,
+    pub total: Option<u64>, // 符合条件的单词总数，只有第一页才数
+    pub next_cursor: Option<String>,
+}
+
+// 上一页最后一个单词的排序键，序列化成字符串交给前端
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordCursor {
+    rank: Option<i32>,
+    text: String,
+    id: i32,
+}
+
+impl WordCursor {
+    // 前端传回来的分页位置，格式不对返回 None
+    pub fn parse(cursor: &str) -> Option<Self> {
+        serde_json::from_str(cursor).ok()
+    }
+
+    fn from_model(m: &master_word::Model) -> Self {
+        Self {
+            rank: m.frequency_rank,
+            text: m.text.clone(),
+            id: m.id,
+        }
+    }
+
+    // 排在这个单词后面：(text, id) 更大；按词频排时先比 rank，没有 rank 的排最后
+    fn after(&self, sort: WordSort) -> Condition {
+        let text_after = Condition::any()
+            .add(master_word::Column::Text.gt(self.text.as_str()))
+            .add(
+                Condition::all()
+                    .add(master_word::Column::Text.eq(self.text.as_str()))
+                    .add(master_word::Column::Id.gt(self.id)),
+            );
+        match (sort, self.rank) {
+            (WordSort::Alphabetical, _) => text_after,
+            (WordSort::Frequency, Some(rank)) => Condition::any()
+                .add(master_word::Column::FrequencyRank.gt(rank))
+                .add(master_word::Column::FrequencyRank.is_null())
+                .add(
+                    Condition::all()
+                        .add(master_word::Column::FrequencyRank.eq(rank))
+                        .add(text_after),
+                ),
+            (WordSort::Frequency, None) => Condition::all()
+                .add(master_word::Column::FrequencyRank.is_null())
+                .add(text_after),
+        }
+    }
 }
 
-//分页查询
+fn list_condition(filter: &WordListFilter) -> Condition {
+    let learning = || {
+        Query::select()
+            .column(user_word::Column::MasterWordId)
+            .from(user_word::Entity)
+            .to_owned()
+    };
+
+    let mut condition = Condition::all()
+        .add(book_condition(filter.book.clone()))
+        .add(rank_condition(filter.max_rank))
+        .add(state_condition(&filter.states));
+    if let Some(l) = &filter.letter {
+        condition = condition.add(starts_with(l));
+    }
+    match filter.learned {
+        Some(true) => condition = condition.add(master_word::Column::Id.in_subquery(learning())),
+        Some(false) => {
+            condition = condition.add(master_word::Column::Id.not_in_subquery(learning()))
+        }
+        None => {}
+    }
+    if filter.min_difficulty.is_some() || filter.max_difficulty.is_some() {
+        let mut band = learning();
+        if let Some(d) = filter.min_difficulty {
+            band.and_where(user_word::Column::Difficulty.gte(d));
+        }
+        if let Some(d) = filter.max_difficulty {
+            band.and_where(user_word::Column::Difficulty.lte(d));
+        }
+        condition = condition.add(master_word::Column::Id.in_subquery(band));
+    }
+    condition
+}
+
+// 一页最多多少个单词，太大的值按这个处理
+pub const MAX_PAGE_SIZE: u64 = 200;
+
+// 第 page 页 (从 0 开始) 前面有多少个结果，页码很大时不溢出
+// SQLite 的 OFFSET 是有符号的 64 位整数，超过 i64::MAX 绑定参数时会出错
+fn page_offset(page: u64, page_size: u64) -> u64 {
+    page.saturating_mul(page_size).min(i64::MAX as u64)
+}
+
+//分页查询：按上一页最后一个单词往后取 (keyset)，不用 OFFSET
+//(text, id) 和 (frequency_rank, text, id) 上有索引，按索引顺序往后读，不用每页都排序
 pub async fn get_words_paginated(
     db: &DatabaseConnection,
+    filter: &WordListFilter,
+    cursor: Option<WordCursor>,
+    page_size: u64,
+) -> Result<WordPage, DbErr> {
+    let page_size = page_size.min(MAX_PAGE_SIZE);
+    let query = MasterWord::find().filter(list_condition(filter));
+    // 总数要扫一遍符合条件的单词，后面的页沿用第一页的
+    let total = match cursor {
+        Some(_) => None,
+        None => Some(query.clone().count(db).await?),
+    };
+
+    let mut query = sorted(query, filter.sort).order_by_asc(master_word::Column::Id);
+    if let Some(c) = &cursor {
+        query = query.filter(c.after(filter.sort));
+    }
+    // 多取一个，判断后面还有没有
+    let mut rows = with_progress(query).limit(page_size + 1).all(db).await?;
+    let next_cursor = if rows.len() as u64 > page_size {
+        rows.truncate(page_size as usize);
+        rows.last()
+            .map(|(m, _)| serde_json::to_string(&WordCursor::from_model(m)).unwrap_or_default())
+    } else {
+        None
+    };
+
+    Ok(WordPage {
+        items: into_entries(db, rows).await?,
+        total,
+        next_cursor,
+    })
+}
+
+// 单词以 prefix 开头，prefix 里的 % 和 _ 不当通配符
+fn starts_with(prefix: &str) -> SimpleExpr {
+    master_word::Column::Text.like(LikeExpr::new(format!("{}%", escape_like(prefix))).escape('\\'))
+}
+
+// 搜索结果，一页一页地取
+#[derive(Debug, Serialize)]
+pub struct SearchResult {
+    pub items: Vec<WordEntry>,
+    pub total: u64,
+    pub fuzzy: bool, // 没有直接匹配的单词，返回的是拼写相近的单词
+}
+
+// 把关键词拆成 FTS5 查询：每个词都按前缀匹配，词之间是 AND
+// 每个词用双引号包起来，避免用户输入被当成 FTS5 语法
+fn fts_query(keyword: &str) -> Option<String> {
+    let terms: Vec<String> = keyword
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("\"{}\"*", t))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+// LIKE 里 % 和 _ 是通配符，用户输入的要转义 (配合 ESCAPE '\\')
+fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+// 全文索引匹配到的单词和相关度 (bm25 越小越相关，单词列的权重比释义高)
+// 作为子查询和 master_word 左连接，只查一次索引
+fn fts_scores(query: &str) -> SelectStatement {
+    Query::select()
+        .expr_as(Expr::cust("rowid"), Alias::new("word_id"))
+        .expr_as(
+            Expr::cust("bm25(master_word_fts, 10.0, 1.0)"),
+            Alias::new("score"),
+        )
+        .from(Alias::new("master_word_fts"))
+        .and_where(Expr::cust_with_values(
+            "master_word_fts MATCH ?",
+            [query.to_owned()],
+        ))
+        .to_owned()
+}
+
+// 释义的拼音在音节边界上匹配其中一种切法 (patterns 来自 hanzi::pinyin_patterns)
+fn pinyin_match(patterns: &[String]) -> String {
+    vec!["instr(' ' || word_sense.pinyin || ' ', ?) > 0"; patterns.len()].join(" OR ")
+}
+
+fn has_han(keyword: &str) -> bool {
+    keyword
+        .chars()
+        .any(|c| ('\u{3400}'..='\u{9fff}').contains(&c))
+}
+
+// 按拆开的中文释义搜索
+// 排名看匹配上的释义有多靠前：第一个释义最核心；同一个释义里整条一致 > 开头一致 > 包含
+async fn sense_search(
+    db: &DatabaseConnection,
+    base: Select<MasterWord>,
+    keyword: &str,
+    sort: Option<WordSort>,
     page: u64,
     page_size: u64,
-) -> Result<Vec<master_word::Model>, DbErr> {
-    MasterWord::find()
-        .order_by_asc(master_word::Column::Text)
+) -> Result<SearchResult, DbErr> {
+    let matched = base.filter(Expr::cust_with_values(
+        "master_word.id IN (SELECT master_word_id FROM word_sense \
+         WHERE instr(word_sense.translation, ?) > 0)",
+        [keyword.to_owned()],
+    ));
+    let total = matched.clone().count(db).await?;
+
+    let matched = match sort {
+        Some(sort) => sorted(matched, sort),
+        None => order_by_frequency(matched.order_by_asc(Expr::cust_with_values(
+            "(SELECT MIN(word_sense.sort_order * 3 + CASE \
+             WHEN word_sense.translation = ? THEN 0 \
+             WHEN instr(word_sense.translation, ?) = 1 THEN 1 ELSE 2 END) \
+             FROM word_sense WHERE word_sense.master_word_id = master_word.id \
+             AND instr(word_sense.translation, ?) > 0)",
+            [keyword.to_owned(), keyword.to_owned(), keyword.to_owned()],
+        )))
+        .order_by_asc(master_word::Column::Text),
+    };
+    let rows = with_progress(matched)
         .limit(page_size)
-        .offset(page * page_size)
+        .offset(page_offset(page, page_size))
         .all(db)
-        .await
+        .await?;
+    let items = into_entries(db, rows).await?;
+
+    Ok(SearchResult {
+        items,
+        total,
+        fuzzy: false,
+    })
 }
 
-//搜索单词
+// master_word.text 不分大小写属于其中之一，能用上 NOCASE 索引
+pub(crate) fn text_in_nocase<I, S>(texts: I) -> SimpleExpr
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let texts: Vec<Value> = texts.into_iter().map(|t| Value::from(t.into())).collect();
+    let placeholders = vec!["?"; texts.len()].join(", ");
+    Expr::cust_with_values(
+        format!("master_word.text COLLATE NOCASE IN ({placeholders})"),
+        texts,
+    )
+}
+
+// 输入是变形时对应的原形 (went -> go)，只返回词库里有的单词
+// word_inflection 表 (内置的不规则变形和词典导入的) 优先，查不到再按规则猜；返回 (master_word id, 变形种类)
+async fn resolve_lemmas(
+    db: &DatabaseConnection,
+    keyword: &str,
+) -> Result<Vec<(i32, Option<String>)>, DbErr> {
+    let form = keyword.trim().to_lowercase();
+    if form.is_empty() || !form.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Ok(Vec::new());
+    }
+
+    let mut lemmas: Vec<(i32, Option<String>)> = WordInflection::find()
+        .filter(word_inflection::Column::Form.eq(form.as_str()))
+        .order_by_asc(word_inflection::Column::Id)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|i| (i.master_word_id, Some(i.kind)))
+        .collect();
+
+    // 输入本身就是词库里的单词时不再按规则猜 (news 不是 new 的复数)
+    let candidates = morphology::lemma_candidates(&form);
+    let found: HashMap<String, i32> = MasterWord::find()
+        .filter(text_in_nocase(
+            std::iter::once(form.clone()).chain(candidates.clone()),
+        ))
+        .select_only()
+        .column(master_word::Column::Text)
+        .column(master_word::Column::Id)
+        .into_tuple::<(String, i32)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|(text, id)| (text.to_lowercase(), id))
+        .collect();
+    if !found.contains_key(&form) {
+        for candidate in candidates {
+            if let Some(&id) = found.get(&candidate) {
+                if !lemmas.iter().any(|(l, _)| *l == id) {
+                    lemmas.push((id, None));
+                }
+            }
+        }
+    }
+    Ok(lemmas)
+}
+
+// 编辑距离 (相邻两个字母换位算一次)，recieve -> receive 是 1
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut prev2: Vec<usize> = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut cur = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            cur[j] = (prev[j] + 1).min(cur[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                cur[j] = cur[j].min(prev2[j - 2] + 1);
+            }
+        }
+        prev2 = std::mem::replace(&mut prev, cur);
+    }
+    prev[b.len()]
+}
+
+// 按编辑距离找拼写相近的单词，距离近的、常用的排前面
+async fn fuzzy_search(
+    db: &DatabaseConnection,
+    base: Select<MasterWord>,
+    keyword: &str,
+    page: u64,
+    page_size: u64,
+) -> Result<SearchResult, DbErr> {
+    let keyword: Vec<char> = keyword.to_lowercase().chars().collect();
+    // 短词只容许错一个字母，不然什么都能匹配上
+    let max_distance = if keyword.len() <= 4 { 1 } else { 2 };
+
+    // 长度差超过 max_distance 的单词距离一定更远，先在 SQL 里筛掉
+    let len = keyword.len() as i64;
+    let words: Vec<(i32, String, Option<i32>)> = base
+        .filter(Expr::cust_with_values(
+            "length(master_word.text) BETWEEN ? AND ?",
+            [len - max_distance as i64, len + max_distance as i64],
+        ))
+        .select_only()
+        .column(master_word::Column::Id)
+        .column(master_word::Column::Text)
+        .column(master_word::Column::FrequencyRank)
+        .into_tuple()
+        .all(db)
+        .await?;
+    let mut matches: Vec<(usize, i32, String, i32)> = words
+        .into_iter()
+        .filter_map(|(id, text, rank)| {
+            let chars: Vec<char> = text.to_lowercase().chars().collect();
+            let distance = edit_distance(&keyword, &chars);
+            (distance <= max_distance).then(|| (distance, rank.unwrap_or(i32::MAX), text, id))
+        })
+        .collect();
+    matches.sort();
+
+    let ids: Vec<i32> = matches
+        .iter()
+        .skip(usize::try_from(page_offset(page, page_size)).unwrap_or(usize::MAX))
+        .take(page_size as usize)
+        .map(|m| m.3)
+        .collect();
+    let mut rows: HashMap<i32, (master_word::Model, Option<user_word::Model>)> =
+        with_progress(MasterWord::find().filter(master_word::Column::Id.is_in(ids.clone())))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|(m, u)| (m.id, (m, u)))
+            .collect();
+    let rows = ids.iter().filter_map(|id| rows.remove(id)).collect();
+
+    Ok(SearchResult {
+        items: into_entries(db, rows).await?,
+        total: matches.len() as u64,
+        fuzzy: true,
+    })
+}
+
+//搜索单词：中文先按拆开的释义找，再用全文索引；英文用全文索引匹配单词和释义，
+//同时当成拼音匹配中文释义 ("pingguo" -> 苹果)，都找不到时按拼写相近的单词找
+//sort 不传时按相关度排序：拼写完全一致的最前，单词本身匹配比释义匹配靠前
 pub async fn search_words(
     db: &DatabaseConnection,
     keyword: String,
-) -> Result<Vec<master_word::Model>, DbErr> {
-    MasterWord::find()
-        .filter(
-            Condition::any()
-                .add(master_word::Column::Text.like(format!("{}%", keyword)))
-                .add(master_word::Column::Definition.like(format!("{}%", keyword))),
+    book: Option<Source>,
+    sort: Option<WordSort>,
+    max_rank: Option<i32>,
+    page: u64,
+    page_size: u64,
+) -> Result<SearchResult, DbErr> {
+    let keyword = keyword.trim();
+    let page_size = page_size.min(MAX_PAGE_SIZE);
+    let base = MasterWord::find()
+        .filter(book_condition(book))
+        .filter(rank_condition(max_rank));
+
+    let han = has_han(keyword);
+    if han {
+        let result = sense_search(db, base.clone(), keyword, sort, page, page_size).await?;
+        if result.total > 0 {
+            return Ok(result);
+        }
+    }
+
+    let pinyin = hanzi::pinyin_patterns(keyword);
+    // 变形 (went / studies) 也能找到原形，排在完全一致的单词后面
+    let lemma_ids: Vec<i32> = resolve_lemmas(db, keyword)
+        .await?
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+
+    if let Some(query) = fts_query(keyword) {
+        let fts = Alias::new("fts");
+        let mut matched = base.clone();
+        QueryTrait::query(&mut matched).join_subquery(
+            JoinType::LeftJoin,
+            fts_scores(&query),
+            fts.clone(),
+            Expr::col((fts.clone(), Alias::new("word_id")))
+                .equals((MasterWord, master_word::Column::Id)),
+        );
+
+        let mut condition =
+            Condition::any().add(Expr::col((fts.clone(), Alias::new("word_id"))).is_not_null());
+        // 中文释义按子串匹配 ("苹果" 能找到 "红苹果")
+        if han {
+            condition = condition.add(Expr::cust_with_values(
+                "master_word.id IN (SELECT rowid FROM master_word_definition_fts \
+                 WHERE definition LIKE ? ESCAPE '\\')",
+                [format!("%{}%", escape_like(keyword))],
+            ));
+        }
+        // 同时当成拼音匹配中文释义 ("fan" 既是 fan 也是 饭，但不是 方)，排在全文索引的结果后面
+        if !pinyin.is_empty() {
+            condition = condition.add(Expr::cust_with_values(
+                format!(
+                    "master_word.id IN (SELECT master_word_id FROM word_sense WHERE {})",
+                    pinyin_match(&pinyin)
+                ),
+                pinyin.clone(),
+            ));
+        }
+        if !lemma_ids.is_empty() {
+            condition = condition.add(master_word::Column::Id.is_in(lemma_ids.clone()));
+        }
+        let mut matched = matched.filter(condition);
+        let total = matched.clone().count(db).await?;
+
+        if total > 0 {
+            matched = match sort {
+                Some(sort) => sorted(matched, sort),
+                None => {
+                    matched = matched.order_by_desc(Expr::cust_with_values(
+                        "master_word.text = ? COLLATE NOCASE",
+                        [keyword.to_owned()],
+                    ));
+                    if !lemma_ids.is_empty() {
+                        matched =
+                            matched.order_by_desc(master_word::Column::Id.is_in(lemma_ids.clone()));
+                    }
+                    matched = matched.order_by_with_nulls(
+                        Expr::col((fts, Alias::new("score"))),
+                        Order::Asc,
+                        NullOrdering::Last,
+                    );
+                    // 只有拼音匹配上的，和按释义搜索一样看匹配上的释义有多靠前
+                    if !pinyin.is_empty() {
+                        matched = order_by_frequency(matched.order_by_with_nulls(
+                            Expr::cust_with_values(
+                                format!(
+                                    "(SELECT MIN(word_sense.sort_order) FROM word_sense \
+                                     WHERE word_sense.master_word_id = master_word.id AND ({}))",
+                                    pinyin_match(&pinyin)
+                                ),
+                                pinyin.clone(),
+                            ),
+                            Order::Asc,
+                            NullOrdering::Last,
+                        ));
+                    }
+                    matched.order_by_asc(master_word::Column::Text)
+                }
+            };
+            let rows = with_progress(matched)
+                .limit(page_size)
+                .offset(page_offset(page, page_size))
+                .all(db)
+                .await?;
+            let items = into_entries(db, rows).await?;
+            return Ok(SearchResult {
+                items,
+                total,
+                fuzzy: false,
+            });
+        }
+    }
+
+    // 中文没有拼写相近的说法
+    if keyword.is_empty() || han {
+        return Ok(SearchResult {
+            items: Vec::new(),
+            total: 0,
+            fuzzy: false,
+        });
+    }
+    fuzzy_search(db, base, keyword, page, page_size).await
+}
+
+// 查单词的结果，带上完整的释义、短语和变形
+#[derive(Debug, Serialize)]
+pub struct WordLookup {
+    pub word: master_word::Model,
+    pub senses: Vec<word_sense::Model>,
+    pub phrases: Vec<word_phrase::Model>,
+    pub inflections: Vec<word_inflection::Model>,
+    pub matched_form: Option<String>, // 输入的是变形时，输入的拼写
+    pub matched_kind: Option<String>, // 变形种类，按规则猜出来的为空
+}
+
+//查单词：原形直接找到，变形 (went / studies / children) 还原成原形
+pub async fn lookup_word(
+    db: &DatabaseConnection,
+    text: String,
+) -> Result<Option<WordLookup>, DbErr> {
+    let text = text.trim().to_lowercase();
+    let exact = MasterWord::find()
+        .filter(text_in_nocase([text.as_str()]))
+        .one(db)
+        .await?;
+
+    let (word, matched_form, matched_kind) = match exact {
+        Some(word) => (word, None, None),
+        None => {
+            let Some((id, kind)) = resolve_lemmas(db, &text).await?.into_iter().next() else {
+                return Ok(None);
+            };
+            let Some(word) = MasterWord::find_by_id(id).one(db).await? else {
+                return Ok(None);
+            };
+            (word, Some(text), kind)
+        }
+    };
+
+    let details = load_word_details(db, &[word.id])
+        .await?
+        .remove(&word.id)
+        .unwrap_or_default();
+    Ok(Some(WordLookup {
+        word,
+        senses: details.senses,
+        phrases: details.phrases,
+        inflections: details.inflections,
+        matched_form,
+        matched_kind,
+    }))
+}
+
+// app_setting 里记录上次计算排名时词频表的指纹
+const FREQUENCY_FINGERPRINT_KEY: &str = "frequency_list_fingerprint";
+
+// 给还没有词频的单词补上排名
+async fn fill_missing_ranks<C: ConnectionTrait>(
+    db: &C,
+    list: &FrequencyList,
+) -> Result<u64, DbErr> {
+    let missing: Vec<(i32, String)> = MasterWord::find()
+        .filter(master_word::Column::FrequencyRank.is_null())
+        .select_only()
+        .column(master_word::Column::Id)
+        .column(master_word::Column::Text)
+        .into_tuple()
+        .all(db)
+        .await?;
+    let ranks: Vec<(i32, i32)> = missing
+        .into_iter()
+        .filter_map(|(id, text)| list.ranks.get(&text.to_lowercase()).map(|&r| (id, r)))
+        .collect();
+    let filled = ranks.len() as u64;
+    db::update_column_by_id(
+        db,
+        master_word::Column::Id,
+        master_word::Column::FrequencyRank,
+        ranks,
+    )
+    .await?;
+    Ok(filled)
+}
+
+//按内置词频表补上还没有词频的单词 (导入单词后调用)，返回补上了多少个
+pub async fn fill_frequency_ranks(
+    db: &DatabaseConnection,
+    registry: &BookRegistry,
+) -> Result<u64, DbErr> {
+    let Some(list) = registry.frequency_list() else {
+        return Ok(0);
+    };
+    let txn = db.begin().await?;
+    let filled = fill_missing_ranks(&txn, &list).await?;
+    txn.commit().await?;
+    Ok(filled)
+}
+
+//启动时调用：词频表换过了才清空重算所有单词的排名，没换就什么都不做
+pub async fn refresh_frequency_ranks(
+    db: &DatabaseConnection,
+    registry: &BookRegistry,
+) -> Result<u64, DbErr> {
+    let Some(list) = registry.frequency_list() else {
+        return Ok(0);
+    };
+    let stored = settings_service::get_state(db, FREQUENCY_FINGERPRINT_KEY).await?;
+    if stored.as_deref() == Some(list.fingerprint.as_str()) {
+        return Ok(0);
+    }
+
+    let txn = db.begin().await?;
+    MasterWord::update_many()
+        .col_expr(
+            master_word::Column::FrequencyRank,
+            Expr::value(Option::<i32>::None),
         )
-        .limit(50)
+        .exec(&txn)
+        .await?;
+    let filled = fill_missing_ranks(&txn, &list).await?;
+    settings_service::set_state(&txn, FREQUENCY_FINGERPRINT_KEY, &list.fingerprint).await?;
+    txn.commit().await?;
+    Ok(filled)
+}
+
+//批量取出单词的释义和短语，按 master_word id 分组
+pub async fn load_word_details(
+    db: &DatabaseConnection,
+    master_ids: &[i32],
+) -> Result<HashMap<i32, WordDetails>, DbErr> {
+    let mut details: HashMap<i32, WordDetails> = HashMap::new();
+    if master_ids.is_empty() {
+        return Ok(details);
+    }
+
+    let senses = WordSense::find()
+        .filter(word_sense::Column::MasterWordId.is_in(master_ids.to_vec()))
+        .order_by_asc(word_sense::Column::SortOrder)
         .all(db)
-        .await
+        .await?;
+    for sense in senses {
+        details
+            .entry(sense.master_word_id)
+            .or_default()
+            .senses
+            .push(sense);
+    }
+
+    let phrases = WordPhrase::find()
+        .filter(word_phrase::Column::MasterWordId.is_in(master_ids.to_vec()))
+        .order_by_asc(word_phrase::Column::SortOrder)
+        .all(db)
+        .await?;
+    for phrase in phrases {
+        details
+            .entry(phrase.master_word_id)
+            .or_default()
+            .phrases
+            .push(phrase);
+    }
+
+    let inflections = WordInflection::find()
+        .filter(word_inflection::Column::MasterWordId.is_in(master_ids.to_vec()))
+        .order_by_asc(word_inflection::Column::Id)
+        .all(db)
+        .await?;
+    for inflection in inflections {
+        details
+            .entry(inflection.master_word_id)
+            .or_default()
+            .inflections
+            .push(inflection);
+    }
+
+    Ok(details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance(a: &str, b: &str) -> usize {
+        let a: Vec<char> = a.chars().collect();
+        let b: Vec<char> = b.chars().collect();
+        edit_distance(&a, &b)
+    }
+
+    #[test]
+    fn edit_distance_counts_single_edits() {
+        assert_eq!(distance("apple", "apple"), 0);
+        assert_eq!(distance("aple", "apple"), 1); // 少一个字母
+        assert_eq!(distance("applle", "apple"), 1); // 多一个字母
+        assert_eq!(distance("appla", "apple"), 1); // 换一个字母
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn edit_distance_treats_transposition_as_one() {
+        assert_eq!(distance("recieve", "receive"), 1);
+        assert_eq!(distance("teh", "the"), 1);
+        assert_eq!(distance("ab", "ba"), 1);
+        assert_eq!(distance("ca", "abc"), 3);
+    }
+
+    #[test]
+    fn fts_query_quotes_each_term() {
+        assert_eq!(
+            fts_query("red apple").as_deref(),
+            Some("\"red\"* \"apple\"*")
+        );
+        assert_eq!(fts_query("\"a OR").as_deref(), Some("\"a\"* \"OR\"*"));
+        assert_eq!(fts_query(" -- "), None);
+    }
+
+    #[test]
+    fn pages_are_bounded() {
+        use crate::services::import_service::{self, ImportFormat};
+        use migration::{Migrator, MigratorTrait};
+        use sea_orm::Database;
+
+        assert_eq!(page_offset(3, 50), 150);
+        assert_eq!(page_offset(u64::MAX, 50), i64::MAX as u64);
+
+        tauri::async_runtime::block_on(async {
+            let db = Database::connect("sqlite::memory:").await.unwrap();
+            Migrator::up(&db, None).await.unwrap();
+            let csv: String = (0..MAX_PAGE_SIZE + 5)
+                .map(|i| format!("word{:03},n. 词\n", i))
+                .collect();
+            import_service::import_word_list(&db, &csv, ImportFormat::Csv, Source::Custom)
+                .await
+                .unwrap();
+
+            let page = get_words_paginated(&db, &WordListFilter::default(), None, u64::MAX)
+                .await
+                .unwrap();
+            assert_eq!(page.items.len() as u64, MAX_PAGE_SIZE);
+            assert!(page.next_cursor.is_some());
+
+            // 页码很大时是空页，不会溢出
+            let result = search_words(&db, "word".to_owned(), None, None, None, u64::MAX, 50)
+                .await
+                .unwrap();
+            assert_eq!(result.total, MAX_PAGE_SIZE + 5);
+            assert!(result.items.is_empty());
+        });
+    }
+
+    #[test]
+    fn pinyin_search_matches_whole_syllables() {
+        use crate::services::import_service::{self, ImportFormat};
+        use migration::{Migrator, MigratorTrait};
+        use sea_orm::Database;
+
+        tauri::async_runtime::block_on(async {
+            let db = Database::connect("sqlite::memory:").await.unwrap();
+            Migrator::up(&db, None).await.unwrap();
+            let csv = "fan,n. 风扇\nsquare,n. 方形\nmeal,n. 一餐; n. 饭\nrice,n. 米饭\n";
+            import_service::import_word_list(&db, csv, ImportFormat::Csv, Source::Custom)
+                .await
+                .unwrap();
+
+            // 单词本身匹配的最前；方 (fang) 不算 fan；只有拼音匹配的按匹配上的释义排，不按字母
+            let texts = |result: SearchResult| -> Vec<String> {
+                result.items.into_iter().map(|e| e.word.text).collect()
+            };
+            let result = search_words(&db, "fan".to_owned(), None, None, None, 0, 10)
+                .await
+                .unwrap();
+            assert_eq!(texts(result), ["fan", "rice", "meal"]);
+
+            let result = search_words(&db, "mi fan".to_owned(), None, None, None, 0, 10)
+                .await
+                .unwrap();
+            assert_eq!(texts(result), ["rice"]);
+            // an 是 fan 的一部分，但不是一个音节，不能匹配 饭
+            let result = search_words(&db, "an".to_owned(), None, None, None, 0, 10)
+                .await
+                .unwrap();
+            assert!(!texts(result).contains(&"rice".to_owned()));
+        });
+    }
 }
@@ -1,3 +1,12 @@
+pub mod anki_import_service;
+pub mod book_service;
+pub mod dictionary_service;
+pub mod export_service;
+pub mod fsrs_params_service;
+pub mod import_service;
 pub mod master_word_service;
+pub mod progress_service;
+pub mod review_log_service;
+pub mod settings_service;
 pub mod stats_service;
 pub mod user_word_service;
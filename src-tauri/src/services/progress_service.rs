@@ -0,0 +1,402 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, DatabaseConnection, DbErr, EntityTrait, QueryOrder,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    clock::Clock,
+    entities::{
+        card_state::CardState,
+        master_word,
+        prelude::{MasterWord, ReviewLog, UserWord},
+        review_log, user_word,
+    },
+};
+
+// 进度文件的格式版本，字段有不兼容的改动时加一
+pub const PROGRESS_FORMAT_VERSION: u32 = 1;
+
+// 导出的进度文件：单词用原文标识，不依赖数据库里的 id
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProgressFile {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub words: Vec<ProgressWord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProgressWord {
+    pub text: String,
+    pub stability: f32,
+    pub difficulty: f32,
+    pub due: DateTime<FixedOffset>,
+    pub last_review: Option<DateTime<FixedOffset>>,
+    pub status: i16,
+    #[serde(default)]
+    pub resume_status: Option<i16>, // 暂停 / 搁置前的状态，旧版本导出的文件没有
+    pub learning_step: i16,
+    pub added_at: DateTime<FixedOffset>,
+    #[serde(default)]
+    pub reviews: Vec<ProgressReview>,
+}
+
+impl ProgressWord {
+    // 检查进度数值，有问题时返回原因
+    fn invalid_reason(&self) -> Option<String> {
+        if CardState::from_i16(self.status).is_none() {
+            return Some(format!("学习状态 {} 无效", self.status));
+        }
+        if let Some(resume) = self.resume_status {
+            if !CardState::from_i16(resume).is_some_and(|s| s.is_active()) {
+                return Some(format!("恢复后的学习状态 {} 无效", resume));
+            }
+        }
+        if !self.stability.is_finite() || self.stability < 0.0 {
+            return Some(format!("稳定性 {} 无效", self.stability));
+        }
+        // 没复习过的新词难度是 0，其余在 1 ~ 10 之间
+        if !(self.difficulty == 0.0 || (1.0..=10.0).contains(&self.difficulty)) {
+            return Some(format!("难度 {} 无效", self.difficulty));
+        }
+        if self.learning_step < 0 {
+            return Some(format!("学习步骤 {} 无效", self.learning_step));
+        }
+        None
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProgressReview {
+    pub rating: i16,
+    pub elapsed_days: f32,
+    pub scheduled_days: f32,
+    pub prev_stability: f32,
+    pub prev_difficulty: f32,
+    pub new_stability: f32,
+    pub new_difficulty: f32,
+    pub duration_ms: Option<i64>,
+    pub reviewed_at: DateTime<FixedOffset>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ProgressImportReport {
+    pub added: u32,           // 本机还没学过，新加入学习的单词
+    pub updated: u32,         // 文件里的进度更新，覆盖了本机进度
+    pub kept: u32,            // 本机进度更新或相同，保留本机
+    pub missing: Vec<String>, // 本机词库里没有的单词
+    pub reviews_added: u32,
+}
+
+//导出全部学习进度和复习记录
+pub async fn export_progress(
+    db: &DatabaseConnection,
+    clock: &dyn Clock,
+) -> Result<ProgressFile, DbErr> {
+    let words = UserWord::find()
+        .find_also_related(MasterWord)
+        .order_by_asc(user_word::Column::Id)
+        .all(db)
+        .await?;
+
+    let mut reviews: HashMap<i32, Vec<ProgressReview>> = HashMap::new();
+    for log in ReviewLog::find()
+        .order_by_asc(review_log::Column::ReviewedAt)
+        .order_by_asc(review_log::Column::Id)
+        .all(db)
+        .await?
+    {
+        reviews
+            .entry(log.user_word_id)
+            .or_default()
+            .push(ProgressReview {
+                rating: log.rating,
+                elapsed_days: log.elapsed_days,
+                scheduled_days: log.scheduled_days,
+                prev_stability: log.prev_stability,
+                prev_difficulty: log.prev_difficulty,
+                new_stability: log.new_stability,
+                new_difficulty: log.new_difficulty,
+                duration_ms: log.duration_ms,
+                reviewed_at: log.reviewed_at,
+            });
+    }
+
+    let words = words
+        .into_iter()
+        .filter_map(|(user, master)| {
+            let master = master?;
+            Some(ProgressWord {
+                text: master.text,
+                stability: user.stability,
+                difficulty: user.difficulty,
+                due: user.due,
+                last_review: user.last_review,
+                status: user.status,
+                resume_status: user.resume_status,
+                learning_step: user.learning_step,
+                added_at: user.added_at,
+                reviews: reviews.remove(&user.id).unwrap_or_default(),
+            })
+        })
+        .collect();
+
+    Ok(ProgressFile {
+        version: PROGRESS_FORMAT_VERSION,
+        exported_at: clock.now(),
+        words,
+    })
+}
+
+//导入进度：按单词原文合并，两边都有时保留最近复习过的那份，复习记录按时间去重后合并
+pub async fn import_progress(
+    db: &DatabaseConnection,
+    file: ProgressFile,
+) -> Result<ProgressImportReport, DbErr> {
+    if file.version > PROGRESS_FORMAT_VERSION {
+        return Err(DbErr::Custom(format!(
+            "进度文件版本 {} 太新，请先升级应用",
+            file.version
+        )));
+    }
+    // 先检查状态和数值，有一个不对整份文件都不导入
+    if let Some((word, reason)) = file
+        .words
+        .iter()
+        .find_map(|w| Some((w, w.invalid_reason()?)))
+    {
+        return Err(DbErr::Custom(format!("单词 {} 的{}", word.text, reason)));
+    }
+
+    let masters: HashMap<String, i32> = MasterWord::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|m: master_word::Model| (m.text.to_lowercase(), m.id))
+        .collect();
+    let mut users: HashMap<i32, user_word::Model> = UserWord::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|u| (u.master_word_id, u))
+        .collect();
+
+    // 同一时刻的复习记录视为同一条
+    let mut reviewed: HashMap<i32, HashSet<i64>> = HashMap::new();
+    for log in ReviewLog::find().all(db).await? {
+        reviewed
+            .entry(log.user_word_id)
+            .or_default()
+            .insert(log.reviewed_at.timestamp_millis());
+    }
+
+    let mut report = ProgressImportReport::default();
+    let mut merged = HashSet::new();
+    let txn = db.begin().await?;
+
+    for word in file.words {
+        let Some(&master_id) = masters.get(&word.text.to_lowercase()) else {
+            report.missing.push(word.text);
+            continue;
+        };
+        // 文件里同一个单词出现多次，只认第一次
+        if !merged.insert(master_id) {
+            continue;
+        }
+
+        let user_word_id = match users.remove(&master_id) {
+            Some(existing) => {
+                let id = existing.id;
+                if word.last_review > existing.last_review {
+                    let mut active_model: user_word::ActiveModel = existing.into();
+                    active_model.stability = Set(word.stability);
+                    active_model.difficulty = Set(word.difficulty);
+                    active_model.due = Set(word.due);
+                    active_model.last_review = Set(word.last_review);
+                    active_model.status = Set(word.status);
+                    active_model.resume_status = Set(word.resume_status);
+                    active_model.learning_step = Set(word.learning_step);
+                    active_model.update(&txn).await?;
+                    report.updated += 1;
+                } else {
+                    report.kept += 1;
+                }
+                id
+            }
+            None => {
+                let inserted = user_word::ActiveModel {
+                    master_word_id: Set(master_id),
+                    stability: Set(word.stability),
+                    difficulty: Set(word.difficulty),
+                    due: Set(word.due),
+                    last_review: Set(word.last_review),
+                    status: Set(word.status),
+                    resume_status: Set(word.resume_status),
+                    added_at: Set(word.added_at),
+                    learning_step: Set(word.learning_step),
+                    ..Default::default()
+                }
+                .insert(&txn)
+                .await?;
+                report.added += 1;
+                inserted.id
+            }
+        };
+
+        let existing = reviewed.remove(&user_word_id).unwrap_or_default();
+        let logs: Vec<review_log::ActiveModel> = word
+            .reviews
+            .into_iter()
+            .filter(|r| !existing.contains(&r.reviewed_at.timestamp_millis()))
+            .map(|r| review_log::ActiveModel {
+                user_word_id: Set(user_word_id),
+                rating: Set(r.rating),
+                elapsed_days: Set(r.elapsed_days),
+                scheduled_days: Set(r.scheduled_days),
+                prev_stability: Set(r.prev_stability),
+                prev_difficulty: Set(r.prev_difficulty),
+                new_stability: Set(r.new_stability),
+                new_difficulty: Set(r.new_difficulty),
+                duration_ms: Set(r.duration_ms),
+                reviewed_at: Set(r.reviewed_at),
+                ..Default::default()
+            })
+            .collect();
+        report.reviews_added += logs.len() as u32;
+        for chunk in logs.chunks(100) {
+            ReviewLog::insert_many(chunk.to_vec()).exec(&txn).await?;
+        }
+    }
+
+    txn.commit().await?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        algorithm::Rating,
+        clock::FixedClock,
+        entities::source_enum::Source,
+        services::{import_service, user_word_service},
+    };
+    use chrono::TimeZone;
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::{Database, PaginatorTrait};
+
+    async fn word_list(csv: &str) -> DatabaseConnection {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+        import_service::import_word_list(
+            &db,
+            csv,
+            import_service::ImportFormat::Csv,
+            Source::Custom,
+        )
+        .await
+        .unwrap();
+        db
+    }
+
+    async fn user_word_of(db: &DatabaseConnection, text: &str) -> user_word::Model {
+        let (user, _) = UserWord::find()
+            .find_also_related(MasterWord)
+            .all(db)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|(_, m)| {
+                m.as_ref()
+                    .is_some_and(|m| m.text.eq_ignore_ascii_case(text))
+            })
+            .unwrap();
+        user
+    }
+
+    #[test]
+    fn export_then_import_round_trips() {
+        tauri::async_runtime::block_on(async {
+            let clock = FixedClock(Utc.with_ymd_and_hms(2025, 3, 1, 10, 0, 0).unwrap());
+            let a = word_list("apple,n. 苹果\nbanana,n. 香蕉\ncat,n. 猫\n").await;
+            for id in [1, 2, 3] {
+                user_word_service::add_word_to_learning(&a, &clock, id)
+                    .await
+                    .unwrap();
+            }
+            user_word_service::submit_review(&a, &clock, 1, Rating::Good, None)
+                .await
+                .unwrap();
+            user_word_service::submit_review(&a, &clock, 2, Rating::Again, None)
+                .await
+                .unwrap();
+            user_word_service::set_card_state(&a, 2, CardState::Suspended)
+                .await
+                .unwrap();
+
+            // 另一台设备只有其中两个单词
+            let b = word_list("Banana,n. 香蕉\napple,n. 苹果\n").await;
+            let json = serde_json::to_string(&export_progress(&a, &clock).await.unwrap()).unwrap();
+            let report = import_progress(&b, serde_json::from_str(&json).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(report.added, 2);
+            assert_eq!(report.missing, vec!["cat"]);
+            assert_eq!(report.reviews_added, 2);
+
+            for text in ["apple", "banana"] {
+                let (from, to) = (user_word_of(&a, text).await, user_word_of(&b, text).await);
+                assert_eq!(to.stability, from.stability);
+                assert_eq!(to.difficulty, from.difficulty);
+                assert_eq!(to.due, from.due);
+                assert_eq!(to.last_review, from.last_review);
+                assert_eq!(to.status, from.status);
+                assert_eq!(to.resume_status, from.resume_status);
+                assert_eq!(to.learning_step, from.learning_step);
+            }
+            let banana = user_word_of(&b, "banana").await;
+            assert_eq!(banana.resume_status, Some(CardState::Learning.as_i16()));
+
+            // 再导一次不会重复
+            let report = import_progress(&b, serde_json::from_str(&json).unwrap())
+                .await
+                .unwrap();
+            assert_eq!((report.added, report.kept, report.reviews_added), (0, 2, 0));
+            assert_eq!(ReviewLog::find().count(&b).await.unwrap(), 2);
+        });
+    }
+
+    #[test]
+    fn rejects_invalid_numbers() {
+        tauri::async_runtime::block_on(async {
+            let clock = FixedClock(Utc.with_ymd_and_hms(2025, 3, 1, 10, 0, 0).unwrap());
+            let db = word_list("apple,n. 苹果\n").await;
+            user_word_service::add_word_to_learning(&db, &clock, 1)
+                .await
+                .unwrap();
+            let json = serde_json::to_string(&export_progress(&db, &clock).await.unwrap()).unwrap();
+
+            let broken: [fn(&mut ProgressWord); 6] = [
+                |w| w.status = 9,
+                |w| w.resume_status = Some(CardState::Suspended.as_i16()),
+                |w| w.stability = f32::NAN,
+                |w| w.stability = -1.0,
+                |w| w.difficulty = 11.0,
+                |w| w.learning_step = -1,
+            ];
+            for breaks in broken {
+                let mut file: ProgressFile = serde_json::from_str(&json).unwrap();
+                breaks(&mut file.words[0]);
+                assert!(import_progress(&db, file).await.is_err());
+            }
+
+            // 旧版本的文件没有 resume_status
+            let old = json.replace(",\"resume_status\":null", "");
+            assert_ne!(old, json);
+            let file: ProgressFile = serde_json::from_str(&old).unwrap();
+            assert_eq!(import_progress(&db, file).await.unwrap().kept, 1);
+        });
+    }
+}
@@ -0,0 +1,16 @@
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::entities::{prelude::ReviewLog, review_log};
+
+//某个单词的全部复习记录，按时间先后排列
+pub async fn get_review_history(
+    db: &DatabaseConnection,
+    user_word_id: i32,
+) -> Result<Vec<review_log::Model>, DbErr> {
+    ReviewLog::find()
+        .filter(review_log::Column::UserWordId.eq(user_word_id))
+        .order_by_asc(review_log::Column::ReviewedAt)
+        .order_by_asc(review_log::Column::Id)
+        .all(db)
+        .await
+}
@@ -0,0 +1,162 @@
+use sea_orm::{
+    sea_query::OnConflict, ActiveValue::Set, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    algorithm,
+    entities::{app_setting, prelude::AppSetting, source_enum::Source},
+    learning_day::LearningDay,
+};
+
+// 新词按什么顺序加入学习
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NewWordOrder {
+    #[default]
+    Random,
+    Alphabetical,
+    BookOrder, // 词书里的原始顺序
+    Frequency, // 常用的词先学
+}
+
+// 后端设置，每个字段存成 app_setting 表里的一行
+// 表里没有的字段用默认值，所以新增字段不需要迁移
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub desired_retention: f32,     // 目标记忆保持率
+    pub learning_steps: Vec<u32>,   // 学习步骤 (分钟)
+    pub relearning_steps: Vec<u32>, // 重学步骤 (分钟)
+    pub enable_fuzz: bool,          // 复习间隔随机浮动
+    pub load_balance: bool,         // 浮动时优先挑复习量少的日子
+    pub day_rollover_hour: u32,     // 每天几点切换到下一个学习日 (本地时间)
+    // 用户所在时区的 IANA 名字 (如 Asia/Shanghai)，夏令时会自动处理
+    // 为空时跟随设备时区；换了时区的设备不会自动更新这里的设置
+    pub timezone: String,
+    pub new_cards_per_day: u32,    // 每个学习日最多新学多少个词
+    pub reviews_per_day: u32,      // 每个学习日最多复习多少个词
+    pub active_books: Vec<Source>, // 新词从这些词书里选，为空就是整个词库
+    pub new_word_order: NewWordOrder,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            desired_retention: algorithm::DEFAULT_DESIRED_RETENTION,
+            learning_steps: algorithm::DEFAULT_LEARNING_STEPS.to_vec(),
+            relearning_steps: algorithm::DEFAULT_RELEARNING_STEPS.to_vec(),
+            enable_fuzz: true,
+            load_balance: true,
+            day_rollover_hour: 4,
+            timezone: String::new(), // 默认跟随设备时区
+            new_cards_per_day: 15,
+            reviews_per_day: 200,
+            active_books: Vec::new(),
+            new_word_order: NewWordOrder::Random,
+        }
+    }
+}
+
+impl Settings {
+    fn validate(&self) -> Result<(), DbErr> {
+        if !(0.7..=0.99).contains(&self.desired_retention) {
+            return Err(DbErr::Custom(
+                "目标保持率必须在 0.70 ~ 0.99 之间".to_owned(),
+            ));
+        }
+        // 步骤必须大于 0，且不超过一天
+        let valid_step = |m: &u32| (1..=1440).contains(m);
+        if !self.learning_steps.iter().all(valid_step)
+            || !self.relearning_steps.iter().all(valid_step)
+        {
+            return Err(DbErr::Custom("学习步骤必须在 1 ~ 1440 分钟之间".to_owned()));
+        }
+        if self.day_rollover_hour > 23 {
+            return Err(DbErr::Custom("切换时间必须在 0 ~ 23 点之间".to_owned()));
+        }
+        if !self.timezone.is_empty() && self.timezone.parse::<chrono_tz::Tz>().is_err() {
+            return Err(DbErr::Custom(format!("不认识的时区: {}", self.timezone)));
+        }
+        Ok(())
+    }
+
+    pub fn learning_day(&self) -> LearningDay {
+        LearningDay::new(&self.timezone, self.day_rollover_hour)
+    }
+}
+
+pub async fn get_settings(db: &DatabaseConnection) -> Result<Settings, DbErr> {
+    let rows = AppSetting::find().all(db).await?;
+
+    let mut map = Map::new();
+    for row in rows {
+        // 单个值解析失败就忽略，退回默认值
+        if let Ok(v) = serde_json::from_str::<Value>(&row.value) {
+            map.insert(row.key, v);
+        }
+    }
+
+    // 整体解析失败 (比如某个字段类型不对) 也退回默认值
+    Ok(serde_json::from_value(Value::Object(map)).unwrap_or_default())
+}
+
+pub async fn update_settings(db: &DatabaseConnection, settings: Settings) -> Result<(), DbErr> {
+    settings.validate()?;
+
+    let Value::Object(map) =
+        serde_json::to_value(&settings).map_err(|e| DbErr::Custom(e.to_string()))?
+    else {
+        return Err(DbErr::Custom("设置序列化失败".to_owned()));
+    };
+
+    let rows: Vec<app_setting::ActiveModel> = map
+        .into_iter()
+        .map(|(key, value)| app_setting::ActiveModel {
+            key: Set(key),
+            value: Set(value.to_string()),
+        })
+        .collect();
+
+    AppSetting::insert_many(rows)
+        .on_conflict(
+            OnConflict::column(app_setting::Column::Key)
+                .update_column(app_setting::Column::Value)
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+// app_setting 里也存一些内部状态 (比如词频表的指纹)，键不在 Settings 里，get_settings 会忽略
+pub(crate) async fn get_state<C: ConnectionTrait>(
+    db: &C,
+    key: &str,
+) -> Result<Option<String>, DbErr> {
+    Ok(AppSetting::find_by_id(key.to_owned())
+        .one(db)
+        .await?
+        .and_then(|row| serde_json::from_str(&row.value).ok()))
+}
+
+pub(crate) async fn set_state<C: ConnectionTrait>(
+    db: &C,
+    key: &str,
+    value: &str,
+) -> Result<(), DbErr> {
+    AppSetting::insert(app_setting::ActiveModel {
+        key: Set(key.to_owned()),
+        value: Set(Value::from(value).to_string()),
+    })
+    .on_conflict(
+        OnConflict::column(app_setting::Column::Key)
+            .update_column(app_setting::Column::Value)
+            .to_owned(),
+    )
+    .exec(db)
+    .await?;
+    Ok(())
+}
@@ -2,9 +2,14 @@ use chrono::Utc;
 use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter};
 use serde::Serialize;
 
-use crate::entities::{
-    prelude::{MasterWord, UserWord},
-    user_word,
+use crate::{
+    clock::Clock,
+    entities::{
+        prelude::{MasterWord, UserWord},
+        user_word,
+    },
+    services::{fsrs_params_service, settings_service, user_word_service},
+    simulator::{self, SimCard},
 };
 
 #[derive(Serialize)]
@@ -12,22 +17,113 @@ pub struct DashboardStats {
     pub total_master: u64,
     pub total_learning: u64,
     pub due_today: u64,
+    pub due_tomorrow: u64,      // 明天这个学习日到期的数量
+    pub due_this_week: u64,     // 从今天起 7 个学习日内到期的数量 (含今天)
+    pub new_remaining: u64,     // 今天还能新学多少个词
+    pub reviews_remaining: u64, // 今天还能复习多少个旧词
 }
 
-pub async fn get_stats(db: &DatabaseConnection) -> Result<DashboardStats, DbErr> {
+pub async fn get_stats(
+    db: &DatabaseConnection,
+    clock: &dyn Clock,
+) -> Result<DashboardStats, DbErr> {
     // 1. 查 master_word 总数
     let total_master = MasterWord::find().count(db).await?;
     // 2. 查 user_word 总数
     let total_learning = UserWord::find().count(db).await?;
-    // 3. 查 user_word 中 due <= now 的数量
+    // 3. 按学习日统计到期数量
+    let now = clock.now();
+    let settings = settings_service::get_settings(db).await?;
+    let learning_day = settings.learning_day();
+    let today_end = learning_day.end_of(now, 0);
+
     let due_today = UserWord::find()
-        .filter(user_word::Column::Due.lte(Utc::now()))
+        .filter(user_word_service::due_today_condition(now, today_end))
+        .count(db)
+        .await?;
+    let active = || {
+        UserWord::find()
+            .filter(user_word::Column::Status.is_not_in(user_word_service::INACTIVE_STATES))
+    };
+    let due_tomorrow = active()
+        .filter(user_word::Column::Due.gte(today_end))
+        .filter(user_word::Column::Due.lt(learning_day.end_of(now, 1)))
+        .count(db)
+        .await?;
+    let due_this_week = active()
+        .filter(user_word::Column::Due.lt(learning_day.end_of(now, 6)))
         .count(db)
         .await?;
 
+    let quota = user_word_service::daily_quota(db, &settings, now).await?;
+
     Ok(DashboardStats {
         total_master,
         total_learning,
         due_today,
+        due_tomorrow,
+        due_this_week,
+        new_remaining: quota.new_remaining,
+        reviews_remaining: quota.reviews_remaining,
     })
 }
+
+#[derive(Serialize)]
+pub struct WorkloadForecast {
+    pub desired_retention: f32,
+    pub daily_reviews: Vec<u32>,
+    pub total_reviews: u32,
+    pub average_per_day: f32,
+    pub memorized: f32, // 模拟结束时预计记住的单词数
+}
+
+// 模拟的上限，太大的值会让模拟跑很久，界面卡住
+pub const MAX_SIMULATION_DAYS: u32 = 3650;
+pub const MAX_SIMULATION_NEW_PER_DAY: u32 = 1000;
+pub const MAX_SIMULATION_RETENTIONS: usize = 20;
+
+// 模拟不同目标保持率下，未来 days 天每天要复习多少
+// 参数范围由调用方 (命令) 检查，这里不再重复
+pub async fn simulate_workload(
+    db: &DatabaseConnection,
+    clock: &dyn Clock,
+    retentions: Vec<f32>,
+    new_per_day: u32,
+    days: u32,
+) -> Result<Vec<WorkloadForecast>, DbErr> {
+    let params = fsrs_params_service::load_params(db, None).await?;
+    let now = clock.now();
+
+    // 暂停和搁置的单词不会出现在复习队列里，不参与模拟
+    let cards: Vec<SimCard> = UserWord::find()
+        .filter(user_word::Column::Status.is_not_in(user_word_service::INACTIVE_STATES))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|w| SimCard {
+            stability: w.stability,
+            difficulty: w.difficulty,
+            due_day: (w.due.with_timezone(&Utc) - now).num_days(),
+            last_review_day: w
+                .last_review
+                .map(|t| -(now - t.with_timezone(&Utc)).num_days())
+                .unwrap_or(0),
+        })
+        .collect();
+
+    let forecasts = retentions
+        .into_iter()
+        .map(|r| {
+            let result = simulator::simulate(&params, r, &cards, new_per_day, days, 42);
+            WorkloadForecast {
+                desired_retention: result.desired_retention,
+                average_per_day: result.total_reviews as f32 / days.max(1) as f32,
+                total_reviews: result.total_reviews,
+                daily_reviews: result.daily_reviews,
+                memorized: result.memorized,
+            }
+        })
+        .collect();
+
+    Ok(forecasts)
+}
@@ -1,43 +1,77 @@
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
 use sea_orm::{
+    prelude::DateTimeWithTimeZone,
     sea_query::Expr,
     ActiveModelTrait,
     ActiveValue::Set,
     // 【关键】必须引入 ColumnTrait 才能使用 UserWord::Column::Due
     ColumnTrait,
+    Condition,
     DatabaseConnection,
     DbErr,
     // 【关键】必须引入 EntityTrait 才能使用 .find()
     EntityTrait,
+    PaginatorTrait,
     // 【关键】必须引入 QueryFilter 才能使用 .filter()
     QueryFilter,
     // 【关键】必须引入 QueryOrder 才能使用 .order_by_asc()
     QueryOrder,
     QuerySelect,
+    TransactionTrait,
 };
+use serde::Serialize;
 
 use crate::{
-    algorithm::{self, Rating},
-    entities::{master_word, prelude::MasterWord, prelude::UserWord, user_word},
-    models::Word,
+    algorithm::{self, CardMemory, Rating, SchedulerConfig},
+    clock::Clock,
+    entities::{
+        card_state::CardState,
+        master_word,
+        prelude::{MasterWord, ReviewLog, UserWord},
+        review_log,
+        source_enum::Source,
+        user_word,
+    },
+    fuzz,
+    services::{
+        book_service, fsrs_params_service, master_word_service,
+        settings_service::{self, NewWordOrder, Settings},
+    },
 };
 
+// 一批新词的第一次到期时间在多少秒内错开
+const NEW_CARD_SPREAD_SECS: i64 = 60 * 60;
+
+// 暂停和搁置的单词不参与复习
+pub const INACTIVE_STATES: [i16; 2] = [CardState::Suspended as i16, CardState::Buried as i16];
+
 pub async fn add_word_to_learning(
     db: &DatabaseConnection,
+    clock: &dyn Clock,
     master_id: i32,
 ) -> Result<user_word::Model, DbErr> {
+    let now = clock.now();
+    // 手动加入的新词和自动生成的新词共用每天的额度
+    let settings = settings_service::get_settings(db).await?;
+    if daily_quota(db, &settings, now).await?.new_remaining == 0 {
+        return Err(DbErr::Custom(format!(
+            "今天已经加入了 {} 个新词，明天再来吧",
+            settings.new_cards_per_day
+        )));
+    }
+
     // 1. 构建要插入的数据 (ActiveModel)
     // 这里我们初始化 SRS (间隔重复) 的默认参数
     let new_learning_record = user_word::ActiveModel {
         master_word_id: Set(master_id), // 关联主词库 ID
 
-        stability: Set(0.0),         // 初始稳定性 (0 表示完全没记住)
-        difficulty: Set(0.0),        // 初始难度 (0 表示默认难度)
-        due: Set(Utc::now().into()), // 到期时间：现在 (意味着添加后立即就可以开始复习)
-        status: Set(0),              // 状态：0 代表 "New" (新单词)
+        stability: Set(0.0),                  // 初始稳定性 (0 表示完全没记住)
+        difficulty: Set(0.0),                 // 初始难度 (0 表示默认难度)
+        due: Set(now.into()),                 // 到期时间：现在 (意味着添加后立即就可以开始复习)
+        status: Set(CardState::New.as_i16()), // 状态：New (新单词)
 
-        last_review: Set(None),           // 还没复习过，所以是 None
-        added_at: Set(Utc::now().into()), // 记录添加时间
+        last_review: Set(None),    // 还没复习过，所以是 None
+        added_at: Set(now.into()), // 记录添加时间
 
         ..Default::default() // ID 会自动生成
     };
@@ -49,44 +83,318 @@ pub async fn add_word_to_learning(
 // 返回值类型是: Vec<(UserWordModel, Option<MasterWordModel>)>
 // SeaORM 的 find_also_related 会返回一个元组
 
+// "今天要复习" 的条件：
+// 新词和复习词只要在今天这个学习日结束前到期就算；学习 / 重学中的词要真正到点才出现
+pub fn due_today_condition(now: DateTime<Utc>, day_end: DateTime<Utc>) -> Condition {
+    let stepping = [CardState::Learning.as_i16(), CardState::Relearning.as_i16()];
+    Condition::any()
+        .add(
+            Condition::all()
+                .add(user_word::Column::Status.is_in(stepping))
+                .add(user_word::Column::Due.lte(now)),
+        )
+        .add(
+            Condition::all()
+                .add(user_word::Column::Status.is_not_in(stepping))
+                .add(user_word::Column::Status.is_not_in(INACTIVE_STATES))
+                .add(user_word::Column::Due.lt(day_end)),
+        )
+}
+
 pub async fn get_due_words(
     db: &DatabaseConnection,
+    clock: &dyn Clock,
 ) -> Result<Vec<(user_word::Model, Option<master_word::Model>)>, DbErr> {
-    UserWord::find()
-        .filter(user_word::Column::Due.lte(Utc::now()))
+    let now = clock.now();
+    let settings = settings_service::get_settings(db).await?;
+    let learning_day = settings.learning_day();
+    let quota = daily_quota(db, &settings, now).await?;
+
+    let words = UserWord::find()
+        .filter(due_today_condition(now, learning_day.end_of(now, 0)))
         .order_by_asc(user_word::Column::Due)
         .find_also_related(master_word::Entity)
         .all(db)
-        .await
+        .await?;
+
+    // 旧词复习量超过今天的额度就截掉，新词和学习中的词不受影响
+    let mut reviews_left = quota.reviews_remaining;
+    Ok(words
+        .into_iter()
+        .filter(|(user, _)| {
+            if user.status != CardState::Review.as_i16() {
+                return true;
+            }
+            if reviews_left == 0 {
+                return false;
+            }
+            reviews_left -= 1;
+            true
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyQuota {
+    pub new_introduced: u64,    // 今天已经加入学习的新词
+    pub new_remaining: u64,     // 今天还能加入多少新词
+    pub reviews_done: u64,      // 今天已经复习过的旧词 (复习时处在 Review 状态)
+    pub reviews_remaining: u64, // 今天还能复习多少旧词
+}
+
+// 按学习日统计今天的新词 / 复习额度
+pub async fn daily_quota(
+    db: &DatabaseConnection,
+    settings: &Settings,
+    now: DateTime<Utc>,
+) -> Result<DailyQuota, DbErr> {
+    let learning_day = settings.learning_day();
+    let day_start = learning_day.start_of(learning_day.day_of(now));
+    let day_end = learning_day.end_of(now, 0);
+
+    let new_introduced = UserWord::find()
+        .filter(user_word::Column::AddedAt.gte(day_start))
+        .filter(user_word::Column::AddedAt.lt(day_end))
+        .count(db)
+        .await?;
+
+    // 今天复习过的旧词，同一个词复习多次只算一次
+    // 和 get_due_words 截断的范围一致：只算复习时处在 Review 状态的，学习 / 重学中的不算
+    let reviews_done = ReviewLog::find()
+        .select_only()
+        .column(review_log::Column::UserWordId)
+        .distinct()
+        .filter(review_log::Column::ReviewedAt.gte(day_start))
+        .filter(review_log::Column::ReviewedAt.lt(day_end))
+        .filter(review_log::Column::PrevStatus.eq(CardState::Review.as_i16()))
+        .count(db)
+        .await?;
+
+    Ok(DailyQuota {
+        new_introduced,
+        new_remaining: (settings.new_cards_per_day as u64).saturating_sub(new_introduced),
+        reviews_done,
+        reviews_remaining: (settings.reviews_per_day as u64).saturating_sub(reviews_done),
+    })
+}
+
+async fn load_scheduler_config(
+    db: &DatabaseConnection,
+    settings: &Settings,
+    deck: Option<&str>,
+) -> Result<SchedulerConfig, DbErr> {
+    Ok(SchedulerConfig {
+        params: fsrs_params_service::load_params(db, deck).await?,
+        desired_retention: settings.desired_retention,
+        learning_steps: settings.learning_steps.clone(),
+        relearning_steps: settings.relearning_steps.clone(),
+    })
+}
+
+// 给毕业后的复习间隔加上随机浮动，开启负载均衡时挑范围内复习量最少的一天
+// 返回调整后的 (到期时间, 间隔天数)
+async fn spread_due(
+    db: &DatabaseConnection,
+    settings: &Settings,
+    user_word_id: i32,
+    interval_days: f32,
+    now: DateTime<Utc>,
+) -> Result<(DateTime<Utc>, f32), DbErr> {
+    let learning_day = settings.learning_day();
+    let seed = fuzz::fuzz_seed(user_word_id, now, &learning_day);
+
+    let days = if settings.load_balance {
+        let (min, max) = fuzz::fuzz_range(interval_days);
+        let dues: Vec<DateTime<Utc>> = UserWord::find()
+            .select_only()
+            .column(user_word::Column::Due)
+            .filter(user_word::Column::Id.ne(user_word_id))
+            .filter(user_word::Column::Status.is_not_in(INACTIVE_STATES))
+            .filter(user_word::Column::Due.gte(learning_day.end_of(now, min - 1)))
+            .filter(user_word::Column::Due.lt(learning_day.end_of(now, max)))
+            .into_tuple::<DateTimeWithTimeZone>()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|t| t.with_timezone(&Utc))
+            .collect();
+        let counts = fuzz::due_day_counts(&dues, now, &learning_day);
+        fuzz::load_balanced_interval(interval_days, seed, &counts)
+    } else {
+        fuzz::fuzzed_interval(interval_days, seed)
+    };
+
+    Ok((now + Duration::days(days), days as f32))
 }
 
 pub async fn submit_review(
     db: &DatabaseConnection,
+    clock: &dyn Clock,
     user_word_id: i32,
-    rating_val: i32,
+    rating: Rating,
+    duration_ms: Option<i64>,
 ) -> Result<(), DbErr> {
-    let word_model = UserWord::find_by_id(user_word_id)
+    let (word_model, master) = UserWord::find_by_id(user_word_id)
+        .find_also_related(master_word::Entity)
         .one(db)
         .await?
         .ok_or(DbErr::RecordNotFound("Word not found".to_owned()))?;
-    let rating = Rating::from_i32(rating_val).unwrap_or(Rating::Good);
-    let result =
-        algorithm::calculate_next_review(word_model.stability, word_model.difficulty, rating);
+    let now = clock.now();
+    let state = CardState::from_i16(word_model.status).unwrap_or(CardState::New);
+    if !state.is_active() {
+        return Err(DbErr::Custom("已暂停或搁置的单词不能复习".to_owned()));
+    }
+
+    // 按单词所属词书读取拟合好的参数
+    let deck = match master {
+        Some(m) => book_service::primary_book(db, m.id).await?,
+        None => None,
+    };
+    let settings = settings_service::get_settings(db).await?;
+    let config = load_scheduler_config(db, &settings, deck.as_deref()).await?;
+    let card = CardMemory {
+        stability: word_model.stability,
+        difficulty: word_model.difficulty,
+        last_review: word_model.last_review.map(|t| t.with_timezone(&Utc)),
+        state,
+        step: word_model.learning_step,
+    };
+    let mut result = algorithm::calculate_next_review(&config, &card, rating, now);
+    if !state.can_transition_to(result.new_state) {
+        return Err(DbErr::Custom(format!(
+            "非法的状态转换: {:?} -> {:?}",
+            state, result.new_state
+        )));
+    }
+
+    // 同一批单词间隔相同，会在同一天扎堆到期，这里把间隔错开
+    if result.new_state == CardState::Review && settings.enable_fuzz {
+        let (next_due, scheduled_days) =
+            spread_due(db, &settings, word_model.id, result.scheduled_days, now).await?;
+        result.next_due = next_due;
+        result.scheduled_days = scheduled_days;
+    }
+
+    // 先记下复习前的状态，写入复习日志
+    let log = review_log::ActiveModel {
+        user_word_id: Set(word_model.id),
+        rating: Set(rating as i16),
+        elapsed_days: Set(result.elapsed_days),
+        scheduled_days: Set(result.scheduled_days),
+        prev_stability: Set(word_model.stability),
+        prev_difficulty: Set(word_model.difficulty),
+        new_stability: Set(result.new_stability),
+        new_difficulty: Set(result.new_difficulty),
+        duration_ms: Set(duration_ms),
+        reviewed_at: Set(now.into()),
+        prev_due: Set(Some(word_model.due)),
+        prev_last_review: Set(word_model.last_review),
+        prev_status: Set(Some(word_model.status)),
+        prev_learning_step: Set(Some(word_model.learning_step)),
+        ..Default::default()
+    };
 
     let mut active_model: user_word::ActiveModel = word_model.into();
 
     active_model.stability = Set(result.new_stability);
     active_model.difficulty = Set(result.new_difficulty);
     active_model.due = Set(result.next_due.into());
-    active_model.last_review = Set(Some(Utc::now().into()));
+    active_model.last_review = Set(Some(now.into()));
 
-    active_model.status = Set(1);
-    active_model.update(db).await?;
+    active_model.status = Set(result.new_state.as_i16());
+    active_model.learning_step = Set(result.new_step);
+
+    // 更新单词和写日志放在同一个事务里，避免只成功一半
+    let txn = db.begin().await?;
+    active_model.update(&txn).await?;
+    log.insert(&txn).await?;
+    txn.commit().await?;
 
     Ok(())
 }
 
-pub async fn generate_daily_new_words(db: &DatabaseConnection, limit: u64) -> Result<u64, DbErr> {
+//撤销最近一次复习：用日志里的快照还原单词，并删掉这条日志
+//可以连续调用，一步步撤销今天的复习；没有可撤销的记录时返回 None
+//复习之后又被暂停、导入覆盖等改动过的单词不撤销，跳过它的记录
+pub async fn undo_last_review(
+    db: &DatabaseConnection,
+    clock: &dyn Clock,
+) -> Result<Option<(user_word::Model, Option<master_word::Model>)>, DbErr> {
+    let now = clock.now();
+    let learning_day = settings_service::get_settings(db).await?.learning_day();
+    let day_start = learning_day.start_of(learning_day.day_of(now));
+
+    // 查找和还原放在同一个事务里，避免查到之后单词又被改掉
+    let txn = db.begin().await?;
+
+    // 只撤销今天、而且带快照的记录
+    let logs = ReviewLog::find()
+        .filter(review_log::Column::ReviewedAt.gte(day_start))
+        .filter(review_log::Column::PrevDue.is_not_null())
+        .order_by_desc(review_log::Column::Id)
+        .find_also_related(UserWord)
+        .all(&txn)
+        .await?;
+    let Some((log, word_model)) = logs.into_iter().find_map(|(log, word)| {
+        let word = word.filter(|w| is_result_of(w, &log))?;
+        Some((log, word))
+    }) else {
+        return Ok(None);
+    };
+
+    let mut active_model: user_word::ActiveModel = word_model.into();
+    active_model.stability = Set(log.prev_stability);
+    active_model.difficulty = Set(log.prev_difficulty);
+    if let Some(due) = log.prev_due {
+        active_model.due = Set(due);
+    }
+    active_model.last_review = Set(log.prev_last_review);
+    if let Some(status) = log.prev_status {
+        active_model.status = Set(status);
+    }
+    if let Some(step) = log.prev_learning_step {
+        active_model.learning_step = Set(step);
+    }
+
+    let restored = active_model.update(&txn).await?;
+    ReviewLog::delete_by_id(log.id).exec(&txn).await?;
+    txn.commit().await?;
+
+    let master = MasterWord::find_by_id(restored.master_word_id)
+        .one(db)
+        .await?;
+    Ok(Some((restored, master)))
+}
+
+// 单词现在的状态是不是这条复习记录留下的：之后没有再复习、导入覆盖或暂停过
+fn is_result_of(word: &user_word::Model, log: &review_log::Model) -> bool {
+    let active = CardState::from_i16(word.status).is_some_and(|s| s.is_active());
+    active
+        && word.last_review == Some(log.reviewed_at)
+        && word.stability == log.new_stability
+        && word.difficulty == log.new_difficulty
+}
+
+// books / order 不传时用设置里的词书和顺序，传了只对这一次生效，不会改设置
+pub async fn generate_daily_new_words(
+    db: &DatabaseConnection,
+    clock: &dyn Clock,
+    limit: u64,
+    books: Option<Vec<Source>>,
+    order: Option<NewWordOrder>,
+) -> Result<u64, DbErr> {
+    let now = clock.now();
+
+    let settings = settings_service::get_settings(db).await?;
+    let books = books.unwrap_or_else(|| settings.active_books.clone());
+    let order = order.unwrap_or(settings.new_word_order);
+
+    // 一天的新词数量由后端控制，重复调用也不会超过设置的上限
+    let limit = limit.min(daily_quota(db, &settings, now).await?.new_remaining);
+    if limit == 0 {
+        return Ok(0);
+    }
+
     let learned_ids: Vec<i32> = UserWord::find()
         .select_only()
         .column(user_word::Column::MasterWordId)
@@ -94,31 +402,44 @@ pub async fn generate_daily_new_words(db: &DatabaseConnection, limit: u64) -> Re
         .all(db)
         .await?;
 
-    // 2. 从 master_words 中随机抽取 'limit' 个不在 learned_ids 中的单词
-    // 注意：SQLite 的随机排序是 ORDER BY RANDOM()
-    let new_words = MasterWord::find()
-        .filter(master_word::Column::Id.is_not_in(learned_ids))
-        .order_by_asc(Expr::cust("RANDOM()".to_string()))
-        .limit(limit)
-        .all(db)
-        .await?;
+    // 2. 从选中的词书里按顺序取 'limit' 个不在 learned_ids 中的单词
+    let mut query = MasterWord::find().filter(master_word::Column::Id.is_not_in(learned_ids));
+    if !books.is_empty() {
+        query = query.filter(book_service::in_books(&books));
+    }
+    query = match order {
+        // 注意：SQLite 的随机排序是 ORDER BY RANDOM()
+        NewWordOrder::Random => query.order_by_asc(Expr::cust("RANDOM()".to_string())),
+        NewWordOrder::Alphabetical => query.order_by_asc(master_word::Column::Text),
+        NewWordOrder::BookOrder => query
+            .order_by_asc(book_service::book_order(&books))
+            .order_by_asc(master_word::Column::Id),
+        NewWordOrder::Frequency => master_word_service::order_by_frequency(query)
+            .order_by_asc(Expr::cust("RANDOM()".to_string())),
+    };
+    let new_words = query.limit(limit).all(db).await?;
 
     if new_words.is_empty() {
         return Ok(0); // 没词了
     };
 
     // 3. 批量插入到 user_words 表
+    // 同一批新词的到期时间错开，不挤在同一个时刻；都在今天结束前，get_due_words 照样能查到
+    let window = (settings.learning_day().end_of(now, 0) - now)
+        .num_seconds()
+        .min(NEW_CARD_SPREAD_SECS);
+    let offsets = fuzz::stagger_offsets(new_words.len(), window);
     let mut active_models = Vec::new();
 
-    for word in &new_words {
+    for (word, offset) in new_words.iter().zip(offsets) {
         active_models.push(user_word::ActiveModel {
             master_word_id: Set(word.id),
             stability: Set(0.0), // 初始状态
             difficulty: Set(0.0),
-            due: Set(Utc::now().into()), // 设为立即到期，这样 get_due_words 就能查到了
-            status: Set(0),              // 0 = New
+            due: Set((now + Duration::seconds(offset)).into()),
+            status: Set(CardState::New.as_i16()),
             last_review: Set(None),
-            added_at: Set(Utc::now().into()),
+            added_at: Set(now.into()),
             ..Default::default()
         });
     }
@@ -127,3 +448,243 @@ pub async fn generate_daily_new_words(db: &DatabaseConnection, limit: u64) -> Re
 
     Ok(new_words.len() as u64)
 }
+
+// 暂停 / 搁置的单词恢复后应该回到的状态
+// 优先用暂停时记下的状态；没有记录时 (比如旧数据) 按有没有复习过推断
+pub fn resume_state(word: &user_word::Model) -> CardState {
+    word.resume_status
+        .and_then(CardState::from_i16)
+        .filter(|s| s.is_active())
+        .unwrap_or(if word.last_review.is_some() {
+            CardState::Review
+        } else {
+            CardState::New
+        })
+}
+
+//手动修改单词状态 (暂停 / 搁置 / 恢复)
+//恢复时 state 必须是暂停前的状态，学习进度原样保留
+pub async fn set_card_state(
+    db: &DatabaseConnection,
+    user_word_id: i32,
+    state: CardState,
+) -> Result<(), DbErr> {
+    let word_model = UserWord::find_by_id(user_word_id)
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound("Word not found".to_owned()))?;
+    let current = CardState::from_i16(word_model.status).unwrap_or(CardState::New);
+
+    // 只允许暂停 / 搁置 / 恢复，学习状态只能通过评分改变
+    let manual = if state.is_active() {
+        !current.is_active() && state == resume_state(&word_model)
+    } else {
+        true
+    };
+    if !manual || !current.can_transition_to(state) {
+        return Err(DbErr::Custom(format!(
+            "非法的状态转换: {:?} -> {:?}",
+            current, state
+        )));
+    }
+
+    // 暂停时记下原来的状态，在暂停和搁置之间切换时保留，恢复后清空
+    let resume_status = match (current.is_active(), state.is_active()) {
+        (true, false) => Some(current.as_i16()),
+        (false, false) => word_model.resume_status,
+        _ => None,
+    };
+    let mut active_model: user_word::ActiveModel = word_model.into();
+    active_model.status = Set(state.as_i16());
+    active_model.resume_status = Set(resume_status);
+    active_model.update(db).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use chrono::TimeZone;
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::Database;
+
+    fn at(hour: u32, minute: u32) -> FixedClock {
+        FixedClock(Utc.with_ymd_and_hms(2025, 3, 1, hour, minute, 0).unwrap())
+    }
+
+    // 空数据库，按 UTC 划分学习日，再放几个单词进主词库
+    async fn setup(words: usize) -> (DatabaseConnection, Vec<i32>) {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+        let settings = Settings {
+            timezone: "UTC".to_owned(),
+            ..Default::default()
+        };
+        settings_service::update_settings(&db, settings)
+            .await
+            .unwrap();
+
+        let mut ids = Vec::new();
+        for i in 0..words {
+            let word = master_word::ActiveModel {
+                text: Set(format!("word{}", i)),
+                definition: Set("释义".to_owned()),
+                created_at: Set(at(8, 0).0.into()),
+                ..Default::default()
+            }
+            .insert(&db)
+            .await
+            .unwrap();
+            ids.push(word.id);
+        }
+        (db, ids)
+    }
+
+    #[test]
+    fn undo_twice_restores_the_card() {
+        tauri::async_runtime::block_on(async {
+            let (db, ids) = setup(1).await;
+            let original = add_word_to_learning(&db, &at(9, 0), ids[0]).await.unwrap();
+
+            submit_review(&db, &at(10, 0), original.id, Rating::Good, None)
+                .await
+                .unwrap();
+            let after_first = UserWord::find_by_id(original.id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            submit_review(&db, &at(10, 10), original.id, Rating::Good, None)
+                .await
+                .unwrap();
+
+            let (undone, _) = undo_last_review(&db, &at(10, 20)).await.unwrap().unwrap();
+            assert_eq!(undone, after_first);
+            let (undone, master) = undo_last_review(&db, &at(10, 20)).await.unwrap().unwrap();
+            assert_eq!(undone, original);
+            assert_eq!(master.map(|m| m.id), Some(ids[0]));
+
+            // 日志都删掉了，没有可以再撤销的
+            assert!(undo_last_review(&db, &at(10, 20)).await.unwrap().is_none());
+            assert_eq!(ReviewLog::find().count(&db).await.unwrap(), 0);
+        });
+    }
+
+    #[test]
+    fn undo_refuses_changed_cards() {
+        tauri::async_runtime::block_on(async {
+            let (db, ids) = setup(1).await;
+            let word = add_word_to_learning(&db, &at(9, 0), ids[0]).await.unwrap();
+            submit_review(&db, &at(10, 0), word.id, Rating::Again, None)
+                .await
+                .unwrap();
+
+            // 复习之后被暂停了，撤销不能把它改回去
+            set_card_state(&db, word.id, CardState::Suspended)
+                .await
+                .unwrap();
+            assert!(undo_last_review(&db, &at(10, 5)).await.unwrap().is_none());
+            let word = UserWord::find_by_id(word.id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(word.status, CardState::Suspended.as_i16());
+            assert_eq!(ReviewLog::find().count(&db).await.unwrap(), 1);
+
+            // 记忆状态被别的地方 (比如导入) 覆盖了，也不撤销
+            set_card_state(&db, word.id, CardState::Learning)
+                .await
+                .unwrap();
+            let mut overwritten: user_word::ActiveModel = word.into();
+            overwritten.stability = Set(42.0);
+            overwritten.update(&db).await.unwrap();
+            assert!(undo_last_review(&db, &at(10, 5)).await.unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn new_words_share_the_daily_quota() {
+        tauri::async_runtime::block_on(async {
+            let (db, ids) = setup(20).await;
+            let settings = settings_service::get_settings(&db).await.unwrap();
+            let clock = at(9, 0);
+
+            add_word_to_learning(&db, &clock, ids[0]).await.unwrap();
+            // 默认每天 15 个，手动加的占掉一个
+            assert_eq!(
+                generate_daily_new_words(&db, &clock, 10, None, None)
+                    .await
+                    .unwrap(),
+                10
+            );
+            assert_eq!(
+                generate_daily_new_words(&db, &clock, 10, None, None)
+                    .await
+                    .unwrap(),
+                4
+            );
+            assert_eq!(
+                generate_daily_new_words(&db, &clock, 10, None, None)
+                    .await
+                    .unwrap(),
+                0
+            );
+            assert!(add_word_to_learning(&db, &clock, ids[19]).await.is_err());
+
+            let quota = daily_quota(&db, &settings, clock.now()).await.unwrap();
+            assert_eq!(quota.new_introduced, 15);
+            assert_eq!(quota.new_remaining, 0);
+
+            // 凌晨 4 点之后是新的学习日，额度重新算
+            let tomorrow = FixedClock(clock.now() + Duration::days(1));
+            let quota = daily_quota(&db, &settings, tomorrow.now()).await.unwrap();
+            assert_eq!(quota.new_remaining, 15);
+            assert_eq!(
+                generate_daily_new_words(&db, &tomorrow, 10, None, None)
+                    .await
+                    .unwrap(),
+                5
+            );
+        });
+    }
+
+    #[test]
+    fn reviews_count_once_per_word() {
+        tauri::async_runtime::block_on(async {
+            let (db, ids) = setup(2).await;
+            let settings = settings_service::get_settings(&db).await.unwrap();
+            let clock = at(10, 0);
+
+            for id in ids {
+                let word = add_word_to_learning(&db, &at(9, 0), id).await.unwrap();
+                let mut review: user_word::ActiveModel = word.into();
+                review.status = Set(CardState::Review.as_i16());
+                review.stability = Set(5.0);
+                review.difficulty = Set(5.0);
+                review.last_review = Set(Some((clock.now() - Duration::days(5)).into()));
+                review.update(&db).await.unwrap();
+            }
+            let words = UserWord::find().all(&db).await.unwrap();
+
+            // 同一个词复习两次只算一次；学习中的词不占复习额度
+            submit_review(&db, &clock, words[0].id, Rating::Again, None)
+                .await
+                .unwrap();
+            submit_review(&db, &at(10, 30), words[0].id, Rating::Good, None)
+                .await
+                .unwrap();
+            let quota = daily_quota(&db, &settings, clock.now()).await.unwrap();
+            assert_eq!(quota.reviews_done, 1);
+            assert_eq!(quota.reviews_remaining, 199);
+
+            submit_review(&db, &clock, words[1].id, Rating::Good, None)
+                .await
+                .unwrap();
+            let quota = daily_quota(&db, &settings, clock.now()).await.unwrap();
+            assert_eq!(quota.reviews_done, 2);
+        });
+    }
+}
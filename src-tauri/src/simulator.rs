@@ -0,0 +1,93 @@
+use crate::{
+    algorithm::{self, Rating},
+    fuzz::SplitMix64,
+};
+
+// 模拟用的单词状态
+#[derive(Debug, Clone, Copy)]
+pub struct SimCard {
+    pub stability: f32,
+    pub difficulty: f32,
+    pub due_day: i64,         // 第几天到期 (0 = 今天，负数 = 已逾期)
+    pub last_review_day: i64, // 上次复习是第几天
+}
+
+pub struct SimulationResult {
+    pub desired_retention: f32,
+    pub daily_reviews: Vec<u32>, // 每天的复习量 (含新词)
+    pub total_reviews: u32,
+    pub memorized: f32, // 模拟结束时所有单词可提取率之和，约等于记住的单词数
+}
+
+// 按目标保持率模拟未来 days 天的复习量
+// 记住的概率取可提取率，记住按 Good、忘记按 Again 处理，每天新学 new_per_day 个词
+pub fn simulate(
+    params: &[f32; 19],
+    desired_retention: f32,
+    cards: &[SimCard],
+    new_per_day: u32,
+    days: u32,
+    seed: u64,
+) -> SimulationResult {
+    let mut rng = SplitMix64(seed);
+    let mut cards = cards.to_vec();
+    let mut daily_reviews = Vec::with_capacity(days as usize);
+
+    for day in 0..days as i64 {
+        for _ in 0..new_per_day {
+            cards.push(SimCard {
+                stability: 0.0,
+                difficulty: 0.0,
+                due_day: day,
+                last_review_day: day,
+            });
+        }
+
+        let mut count = 0;
+        for card in cards.iter_mut().filter(|c| c.due_day <= day) {
+            let elapsed_days = (day - card.last_review_day) as f32;
+            let rating = if card.stability <= 0.0 {
+                Rating::Good
+            } else {
+                let r = algorithm::retrievability(elapsed_days, card.stability);
+                if rng.next_f32() < r {
+                    Rating::Good
+                } else {
+                    Rating::Again
+                }
+            };
+
+            let (s, d) = algorithm::next_memory_state(
+                params,
+                card.stability,
+                card.difficulty,
+                elapsed_days,
+                rating,
+            );
+            let interval = match rating {
+                Rating::Again => 1,
+                _ => algorithm::next_interval(s, desired_retention).round() as i64,
+            };
+
+            card.stability = s;
+            card.difficulty = d;
+            card.last_review_day = day;
+            card.due_day = day + interval;
+            count += 1;
+        }
+        daily_reviews.push(count);
+    }
+
+    let memorized = cards
+        .iter()
+        .filter(|c| c.stability > 0.0)
+        .map(|c| algorithm::retrievability((days as i64 - c.last_review_day) as f32, c.stability))
+        .sum();
+
+    SimulationResult {
+        desired_retention,
+        total_reviews: daily_reviews.iter().sum(),
+        daily_reviews,
+        memorized,
+    }
+}
@@ -1,18 +1,54 @@
 import { invoke } from "@tauri-apps/api/core";
-import { DashboardStats, MasterWord, ReviewCard } from "../types";
+import { listen } from "@tauri-apps/api/event";
+import {
+  AnkiImportOptions,
+  AnkiImportReport,
+  BookInfo,
+  CardState,
+  DashboardStats,
+  DictionaryFinished,
+  DictionaryProgress,
+  ExportFilter,
+  ExportFormat,
+  ImportFormat,
+  ImportReport,
+  MasterWord,
+  NewWordOrder,
+  OptimizeReport,
+  ProgressImportReport,
+  ReviewCard,
+  ReviewLog,
+  SearchResult,
+  Settings,
+  Source,
+  WordEntry,
+  WordListFilter,
+  WordLookup,
+  WordPage,
+  WordSort,
+  WorkloadForecast,
+} from "../types";
 
 export const dueWordsAPI = () => {
   return invoke<ReviewCard[]>("get_due_words");
 };
 
-export const masterWordsAPI = () => {
-  return invoke<MasterWord[]>("get_all_master_words");
+// book 不传时返回整个词库
+export const masterWordsAPI = (book?: Source) => {
+  return invoke<WordEntry[]>("get_all_master_words", { book });
 };
 
-export const masterWordsByFristLetterAPI = (letter?: string) => {
+// states 不为空时只返回状态在其中的在学单词
+export const masterWordsByFristLetterAPI = (
+  letter?: string,
+  book?: Source,
+  states?: CardState[]
+) => {
   // 传入 Option<String> 给 Rust，对应 JS 的 string | null
-  return invoke<MasterWord[]>("get_master_word_by_first_letter", {
+  return invoke<WordEntry[]>("get_master_word_by_first_letter", {
     letter: letter || null,
+    book: book || null,
+    states: states || null,
   });
 };
 
@@ -29,21 +65,204 @@ export const addToLearningAPI = (masterId: number) => {
 // 关键：键名必须和 Rust 函数参数名对应 (驼峰转下划线)
 // Rust: user_word_id -> JS: userWordId
 // Rust: rating_val   -> JS: ratingVal
-export const submitReviewAPI = (ratingVal: number, userWordId: number) => {
+export const submitReviewAPI = (
+  ratingVal: number,
+  userWordId: number,
+  durationMs?: number
+) => {
   return invoke("submit_review", {
     userWordId: userWordId,
     ratingVal: ratingVal,
+    durationMs: durationMs ?? null,
   });
 };
 
-export const getWordsListFiliterAPI = (page: number, limit: number = 20) => {
-  return invoke<MasterWord[]>("get_words_list_filiter", { page, limit });
+// 撤销最近一次复习，返回被还原的卡片 (没有可撤销的记录时为 null)
+// 可以连续调用，逐步撤销今天的复习
+export const undoLastReviewAPI = () => {
+  return invoke<ReviewCard | null>("undo_last_review");
 };
 
-export const searchWordsAPI = (keyword: string) => {
-  return invoke<MasterWord[]>("search_master_words", { keyword });
+// 暂停 / 搁置 / 恢复单词，恢复时 state 传暂停前的状态 (进度不会重置)
+export const setCardStateAPI = (userWordId: number, state: CardState) => {
+  return invoke("set_card_state", { userWordId, state });
 };
 
-export const generateNewWordsAPI = (limit: number) => {
-  return invoke<number>("generate_new_words", { limit });
+export const reviewHistoryAPI = (userWordId: number) => {
+  return invoke<ReviewLog[]>("get_review_history", { userWordId });
+};
+
+// cursor 用上一页返回的 next_cursor，第一页不传
+export const getWordsListFiliterAPI = (
+  cursor?: string,
+  limit: number = 20,
+  filter?: WordListFilter
+) => {
+  return invoke<WordPage>("get_words_list_filiter", {
+    cursor: cursor || null,
+    limit,
+    filter: filter || null,
+  });
+};
+
+// sort 不传时按相关度排序，page 从 0 开始
+export const searchWordsAPI = (
+  keyword: string,
+  book?: Source,
+  sort?: WordSort,
+  maxRank?: number,
+  page: number = 0,
+  limit: number = 50
+) => {
+  return invoke<SearchResult>("search_master_words", {
+    keyword,
+    book: book || null,
+    sort: sort || null,
+    maxRank: maxRank ?? null,
+    page,
+    limit,
+  });
+};
+
+// 查单词，变形 (went / studies) 会还原成原形，找不到时为 null
+export const lookupWordAPI = (text: string) => {
+  return invoke<WordLookup | null>("lookup_word", { text });
+};
+
+// books / order 不传时用设置里的选择，传了只对这一次生效；要保存请调用 updateSettingsAPI
+export const generateNewWordsAPI = (
+  limit: number,
+  books?: Source[],
+  order?: NewWordOrder
+) => {
+  return invoke<number>("generate_new_words", {
+    limit,
+    books: books || null,
+    order: order || null,
+  });
+};
+
+// 用自己的复习记录拟合记忆参数，deck 为空代表全局
+export const optimizeParamsAPI = (deck?: string) => {
+  return invoke<OptimizeReport>("optimize_fsrs_params", { deck: deck || null });
+};
+
+export const applyParamsAPI = (params: number[], deck?: string) => {
+  return invoke("apply_fsrs_params", { deck: deck || null, params });
+};
+
+// 后端设置 (目标保持率等)，保存在数据库里
+export const getSettingsAPI = () => {
+  return invoke<Settings>("get_settings");
+};
+
+export const updateSettingsAPI = (settings: Settings) => {
+  return invoke("update_settings", { settings });
+};
+
+// 模拟不同目标保持率下未来 days 天的复习量
+export const simulateWorkloadAPI = (
+  retentions: number[],
+  newPerDay: number,
+  days: number = 30
+) => {
+  return invoke<WorkloadForecast[]>("simulate_workload", {
+    retentions,
+    newPerDay,
+    days,
+  });
+};
+
+// 预览某一天 (ISO 时间字符串) 的复习情况，不传则回到真实时间
+// 预览期间只能查看，复习、加词、撤销等会返回错误
+// 返回后端当前使用的时间
+export const setPreviewDateAPI = (date?: string) => {
+  return invoke<string>("set_preview_date", { date: date || null });
+};
+
+// 导入自己的词表，path 由文件选择框得到；format 不传时按扩展名判断，.txt 等按内容判断
+export const importWordListAPI = (
+  path: string,
+  source?: Source,
+  format?: ImportFormat
+) => {
+  return invoke<ImportReport>("import_word_list", {
+    path,
+    format: format || null,
+    source: source || null,
+  });
+};
+
+// 导入 Anki 牌组 (.apkg)
+export const importAnkiDeckAPI = (path: string, options?: AnkiImportOptions) => {
+  return invoke<AnkiImportReport>("import_anki_deck", {
+    path,
+    options: options || null,
+  });
+};
+
+// 导出学习进度到 path (JSON)，返回导出的单词数；用于手机和电脑之间迁移
+export const exportProgressAPI = (path: string) => {
+  return invoke<number>("export_progress", { path });
+};
+
+// 合并 export_progress 导出的进度文件，按单词原文匹配
+export const importProgressAPI = (path: string) => {
+  return invoke<ProgressImportReport>("import_progress", { path });
+};
+
+// 按条件导出单词 (CSV 或 Anki 文本)，返回导出的单词数
+export const exportWordsAPI = (
+  path: string,
+  format: ExportFormat,
+  filter?: ExportFilter
+) => {
+  return invoke<number>("export_words", {
+    path,
+    format,
+    filter: filter || null,
+  });
+};
+
+// 内置词书列表
+export const listBooksAPI = () => {
+  return invoke<BookInfo[]>("list_books");
+};
+
+// 没有词频表时不能按词频排序、筛选或选新词
+export const hasFrequencyListAPI = () => {
+  return invoke<boolean>("has_frequency_list");
+};
+
+export const installBookAPI = (source: Source) => {
+  return invoke<ImportReport>("install_book", { source });
+};
+
+// 返回删除的单词数，正在学的单词不会删除
+export const uninstallBookAPI = (source: Source) => {
+  return invoke<number>("uninstall_book", { source });
+};
+
+// 用用户自己下载的词典文件 (ECDICT 格式的 CSV，比如 ecdict.csv) 补全音标、释义和单词变形
+// 安装包里不带词典；在后台跑，返回任务编号，进度和结果分别用 onDictionaryProgress / onDictionaryFinished 监听
+export const enrichFromDictionaryCsvAPI = (path: string) => {
+  return invoke<number>("enrich_from_dictionary_csv", { path });
+};
+
+// 监听词典补全进度，返回取消监听的函数
+export const onDictionaryProgress = (
+  handler: (progress: DictionaryProgress) => void
+) => {
+  return listen<DictionaryProgress>("dictionary-progress", (event) =>
+    handler(event.payload)
+  );
+};
+
+// 监听词典补全结果 (成功或失败)，返回取消监听的函数
+export const onDictionaryFinished = (
+  handler: (finished: DictionaryFinished) => void
+) => {
+  return listen<DictionaryFinished>("dictionary-finished", (event) =>
+    handler(event.payload)
+  );
 };
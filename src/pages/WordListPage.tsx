@@ -11,8 +11,8 @@ import {
   CircularProgress,
   IconButton,
   Divider,
-  Chip,
   Box,
+  Chip,
   Tooltip,
   InputBase,
   Paper as InputPaper,
@@ -26,6 +26,28 @@ import {
   AllInclusive as AllIcon,
 } from "@mui/icons-material";
 import useTTS from "../hooks/useTTS";
+import { CardState, Source } from "../types";
+
+// 学习状态的中文名，没在学的单词不显示
+const STATE_LABELS: Record<CardState, string> = {
+  New: "新词",
+  Learning: "学习中",
+  Review: "复习中",
+  Relearning: "重学中",
+  Suspended: "已暂停",
+  Buried: "已搁置",
+};
+
+// 词书的中文名，和 word_book 表里的 name 一致
+const BOOK_LABELS: Record<Source, string> = {
+  ElementarySchool: "小学",
+  JuniorHighSchool: "初中",
+  HighSchool: "高中",
+  Cet4: "大学英语四级",
+  Cet6: "大学英语六级",
+  Custom: "自定义",
+  Other: "其他",
+};
 
 // 生成 A-Z 的字母数组
 const ALPHABET = Array.from({ length: 26 }, (_, i) =>
@@ -212,18 +234,33 @@ export default function WordListPage() {
                                 /{word.pronunciation}/
                               </span>
                             )}
+
+                            {word.state && (
+                              <Chip
+                                label={STATE_LABELS[word.state]}
+                                size="small"
+                                color={
+                                  word.state === "Suspended" ||
+                                  word.state === "Buried"
+                                    ? "default"
+                                    : "primary"
+                                }
+                                variant="outlined"
+                              />
+                            )}
                           </div>
                         }
                         secondary={
                           <Box component="div">
-                            {word.source && (
+                            {word.books.map((book) => (
                               <Chip
-                                label={word.source}
+                                key={book}
+                                label={BOOK_LABELS[book]}
                                 size="small"
                                 variant="outlined"
                                 className="mb-1 mr-2 text-xs border-gray-200 text-gray-500 h-5"
                               />
-                            )}
+                            ))}
                             <span className="text-gray-600 block leading-relaxed">
                               {word.definition}
                             </span>
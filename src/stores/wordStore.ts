@@ -1,4 +1,4 @@
-import { DashboardStats, MasterWord, ReviewCard } from "../types";
+import { DashboardStats, ReviewCard, WordEntry } from "../types";
 import { create } from "zustand";
 import { getStore } from "../utils/store"; // 引入刚才创建的实例
 import {
@@ -14,13 +14,14 @@ import {
 } from "../api";
 
 interface WordState {
-  masterWords: MasterWord[];
+  masterWords: WordEntry[];
   reviewQueue: ReviewCard[];
   currentLetter: string;
   stats: DashboardStats | null;
   isLoading: boolean;
   error: string | null;
-  page: number;
+  cursor: string | null; // 下一页从哪里开始
+  total: number; // 单词总数
   hasMore: boolean; // 是否还有更多数据
 
   // 搜索状态
@@ -57,7 +58,8 @@ export const useWordStore = create<WordState>((set, get) => ({
   isLoading: false,
   error: null,
   currentLetter: "#",
-  page: 0,
+  cursor: null,
+  total: 0,
   hasMore: true,
   isSearching: false,
   dailyLimit: 15,
@@ -67,7 +69,7 @@ export const useWordStore = create<WordState>((set, get) => ({
     set({ currentLetter: letter });
     // 切换字母后，立即拉取新数据
     if (letter === "#") {
-      // 如果是 #，重置回无限滚动列表（即 page=0，重新加载）
+      // 如果是 #，重置回无限滚动列表（清空 cursor，重新加载）
       get().resetList();
     } else {
       // 如果是字母，调用按字母查询的接口
@@ -205,18 +207,19 @@ export const useWordStore = create<WordState>((set, get) => ({
 
     set({ isLoading: true });
     try {
-      const currentPage = get().page;
+      const cursor = get().cursor ?? undefined;
       const limit = 20;
 
-      const newWords = await getWordsListFiliterAPI(currentPage, limit);
+      const page = await getWordsListFiliterAPI(cursor, limit);
 
       set((state) => ({
         // 【关键】追加数据，而不是覆盖
-        masterWords: [...state.masterWords, ...newWords],
-        page: state.page + 1,
+        masterWords: [...state.masterWords, ...page.items],
+        cursor: page.next_cursor ?? null,
+        total: page.total ?? state.total,
         isLoading: false,
-        // 如果取回来的数据少于 limit，说明到底了
-        hasMore: newWords.length === limit,
+        // 没有 next_cursor 说明到底了
+        hasMore: !!page.next_cursor,
       }));
     } catch (err) {
       console.error(err);
@@ -236,7 +239,8 @@ export const useWordStore = create<WordState>((set, get) => ({
     try {
       const results = await searchWordsAPI(keyword);
       set({
-        masterWords: results, // 搜索模式下是覆盖数据
+        masterWords: results.items, // 搜索模式下是覆盖数据
+        total: results.total,
         isLoading: false,
       });
     } catch (err) {
@@ -249,7 +253,7 @@ export const useWordStore = create<WordState>((set, get) => ({
   resetList: async () => {
     set({
       masterWords: [],
-      page: 0,
+      cursor: null,
       hasMore: true,
       isSearching: false,
       isLoading: false,
@@ -4,18 +4,68 @@ export interface MasterWord {
   id: number;
   text: string;
   definition: string;
-  source?: string; // Rust 中是 Option<String>，对应 TS 的 string | undefined
-  pronunciation?: string;
+  pronunciation?: string; // Rust 中是 Option<String>，对应 TS 的 string | undefined
   audio_url?: string;
   created_at: string; // 传过来通常是 ISO 8601 字符串
+  frequency_rank?: number; // 词频排名，1 是最常用的词
+  pronunciation_us?: string;
+  pronunciation_uk?: string;
+}
+
+// 对应 Rust 后端的 WordEntry：单词加上学习进度，没在学时进度字段为空
+export interface WordEntry extends MasterWord {
+  user_word_id?: number; // user_words 表的主键
+  state?: CardState;
+  stability?: number;
+  due?: string; // 下次复习时间 ISO 字符串
+  books: Source[]; // 单词属于哪些词书
+}
+
+// 对应 Rust 后端的 WordListFilter，不填的条件不限制
+export interface WordListFilter {
+  letter?: string; // 首字母
+  book?: Source;
+  learned?: boolean; // true 只看在学的，false 只看没学的
+  min_difficulty?: number; // 难度范围 (1-10)
+  max_difficulty?: number;
+  max_rank?: number; // 只看词频排名在前 max_rank 的单词
+  sort?: WordSort;
+  states?: CardState[]; // 只看这些学习状态的单词
+}
+
+// 对应 Rust 后端的 WordPage (一页单词)
+export interface WordPage {
+  items: WordEntry[];
+  total?: number; // 符合条件的单词总数，只有第一页返回
+  next_cursor?: string; // 传回去取下一页，为空说明到底了
 }
 
+// 对应 Rust 后端的 SearchResult (一页搜索结果)
+export interface SearchResult {
+  items: WordEntry[];
+  total: number;
+  fuzzy: boolean; // 没有直接匹配的单词，返回的是拼写相近的单词
+}
+
+// 单词列表的排序方式，Frequency 是常用的在前 (安装包里有词频表时才能用)
+export type WordSort = "Alphabetical" | "Frequency";
+
 // 对应 Rust 后端的 ApiError
 export interface ApiError {
   DatabaseError?: string;
+  InvalidInput?: string;
   // 其他可能的错误类型
 }
 
+// 对应 Rust 后端的 CardState (user_word.status)
+export type CardState =
+  | "New"
+  | "Learning"
+  | "Review"
+  | "Relearning"
+  | "Suspended"
+  | "Buried";
+
 // 【新增】对应 Rust 后端的 ReviewCard
 export interface ReviewCard {
   id: number; // user_words 表的主键 (用于更新进度)
@@ -24,13 +74,209 @@ export interface ReviewCard {
   text: string; // 单词
   definition: string; // 释义
   pronunciation?: string;
+  pronunciation_us?: string; // 美式音标
+  pronunciation_uk?: string; // 英式音标
   due: string; // 到期时间 ISO 字符串
+  state: CardState;
   stability: number;
   difficulty: number;
+  senses: WordSense[]; // 按词性拆开的释义
+  phrases: WordPhrase[]; // 短语
+  inflections: WordInflection[]; // 变形 (过去式、复数等)
+}
+
+// 对应 word_sense 表
+export interface WordSense {
+  id: number;
+  master_word_id: number;
+  pos?: string; // 词性，如 n / v / adj
+  translation: string;
+  sort_order: number;
+  pinyin?: string; // 释义的拼音，不带声调
+}
+
+// 对应 word_inflection 表
+export interface WordInflection {
+  id: number;
+  master_word_id: number;
+  form: string; // 变形后的拼写
+  // past / past_participle / present_participle / third_person / present /
+  // plural / comparative / superlative
+  kind: string;
+}
+
+// 对应 Rust 后端的 WordLookup (查单词结果)
+export interface WordLookup {
+  word: MasterWord;
+  senses: WordSense[];
+  phrases: WordPhrase[];
+  inflections: WordInflection[];
+  matched_form?: string; // 输入的是变形时，输入的拼写
+  matched_kind?: string; // 变形种类，按规则猜出来的为空
+}
+
+// 对应 word_phrase 表
+export interface WordPhrase {
+  id: number;
+  master_word_id: number;
+  phrase: string;
+  translation: string;
+  sort_order: number;
 }
 
 export interface DashboardStats {
   total_master: number;
   total_learning: number;
   due_today: number;
+  due_tomorrow: number; // 明天这个学习日到期的数量
+  due_this_week: number; // 7 个学习日内到期的数量 (含今天)
+  new_remaining: number; // 今天还能新学多少个词
+  reviews_remaining: number; // 今天还能复习多少个旧词
+}
+
+// 对应 Rust 后端的 review_log::Model
+export interface ReviewLog {
+  id: number;
+  user_word_id: number;
+  rating: number; // 1 Again / 2 Hard / 3 Good / 4 Easy
+  elapsed_days: number;
+  scheduled_days: number;
+  prev_stability: number;
+  prev_difficulty: number;
+  new_stability: number;
+  new_difficulty: number;
+  duration_ms?: number;
+  reviewed_at: string;
+  prev_due?: string;
+  prev_last_review?: string;
+  prev_status?: number;
+  prev_learning_step?: number;
+}
+
+// 对应 Rust 后端的 OptimizeReport
+export interface OptimizeReport {
+  deck?: string;
+  params: number[];
+  loss_before: number;
+  loss_after: number;
+  review_count: number;
+}
+
+// 对应 Rust 后端的 Settings
+export interface Settings {
+  desired_retention: number; // 目标记忆保持率 0.70 ~ 0.99
+  learning_steps: number[]; // 学习步骤 (分钟)
+  relearning_steps: number[]; // 重学步骤 (分钟)
+  enable_fuzz: boolean; // 复习间隔随机浮动
+  load_balance: boolean; // 浮动时优先挑复习量少的日子
+  day_rollover_hour: number; // 每天几点切换到下一个学习日
+  // 时区的 IANA 名字，如 Asia/Shanghai，为空时跟随设备时区
+  // 可以用 Intl.DateTimeFormat().resolvedOptions().timeZone 取得
+  timezone: string;
+  new_cards_per_day: number; // 每天最多新学多少个词
+  reviews_per_day: number; // 每天最多复习多少个词
+  active_books: Source[]; // 新词从这些词书里选，为空就是整个词库
+  new_word_order: NewWordOrder;
+}
+
+// 新词按什么顺序加入学习，BookOrder 是词书里的原始顺序，Frequency 要有词频表
+export type NewWordOrder = "Random" | "Alphabetical" | "BookOrder" | "Frequency";
+
+// 对应 Rust 后端的 WorkloadForecast
+export interface WorkloadForecast {
+  desired_retention: number;
+  daily_reviews: number[];
+  total_reviews: number;
+  average_per_day: number;
+  memorized: number;
+}
+
+// 对应 Rust 后端的 Source (词库来源)
+export type Source =
+  | "ElementarySchool"
+  | "JuniorHighSchool"
+  | "HighSchool"
+  | "Cet4"
+  | "Cet6"
+  | "Custom"
+  | "Other";
+
+export type ImportFormat = "Csv" | "Tsv" | "Json";
+
+// 对应 Rust 后端的 ImportReport
+export interface ImportReport {
+  inserted: number;
+  skipped: string[]; // 词库里已有或文件里重复的单词
+  invalid: {
+    line: number; // 第几行 (JSON 为第几个元素)，从 1 开始
+    content: string;
+    reason: string;
+  }[];
+}
+
+// 对应 Rust 后端的 AnkiImportOptions，字段名为空时用默认规则
+export interface AnkiImportOptions {
+  text_field?: string; // 单词所在字段名，默认第一个字段
+  definition_field?: string; // 释义所在字段名，默认第二个字段
+  pronunciation_field?: string; // 音标所在字段名，默认按字段名猜
+  import_history?: boolean; // 是否把 Anki 的复习记录换算成学习进度
+  source?: Source;
+}
+
+// 对应 Rust 后端的 AnkiImportReport
+export interface AnkiImportReport extends ImportReport {
+  media_copied: number;
+  scheduled: number; // 带着复习进度导入的单词数
+}
+
+// 对应 Rust 后端的 ProgressImportReport
+export interface ProgressImportReport {
+  added: number; // 本机还没学过，新加入学习的单词
+  updated: number; // 文件里的进度更新，覆盖了本机进度
+  kept: number; // 本机进度更新或相同，保留本机
+  missing: string[]; // 本机词库里没有的单词
+  reviews_added: number;
+}
+
+export type ExportFormat = "Csv" | "AnkiText";
+
+// 对应 Rust 后端的 ExportFilter，不填的条件不限制
+export interface ExportFilter {
+  states?: CardState[];
+  min_difficulty?: number; // 难度大于这个值 (1-10)
+  books?: Source[]; // 属于其中任意一本词书
+  due_from?: string; // 到期时间范围，ISO 字符串
+  due_to?: string;
+}
+
+// 对应 Rust 后端的 BookInfo (内置词书)
+export interface BookInfo {
+  source: Source;
+  name: string;
+  available: boolean; // 安装包里有没有这本词书的数据
+  word_count?: number; // 词书里的单词数，还没安装过时为空
+  installed_count: number; // 词库里已经有多少个属于这本词书的单词
+}
+
+// 对应 Rust 后端的 DictionaryReport (词典补全结果)
+export interface DictionaryReport {
+  matched: number; // 词典里找到的词库单词
+  pronunciations: number; // 补上音标的单词
+  senses_added: number; // 新增的释义
+  inflections_added: number; // 新增的变形
+}
+
+// dictionary-progress 事件的内容
+export interface DictionaryProgress {
+  job_id: number; // enrichFromDictionaryCsvAPI 返回的任务编号
+  read_bytes: number;
+  total_bytes: number;
+  matched: number;
+}
+
+// dictionary-finished 事件的内容，report 和 error 只有一个不为空
+export interface DictionaryFinished {
+  job_id: number;
+  report?: DictionaryReport;
+  error?: string;
 }